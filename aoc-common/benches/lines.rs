@@ -0,0 +1,108 @@
+//! Sums calibration values from a generated document, reading it line by line then with
+//! `sum_lines` on one thread and on all of them. The size in MiB can be set with
+//! `LINES_BENCH_MIB`, for example `LINES_BENCH_MIB=4096 cargo bench -p aoc-common`
+
+use aoc_common::{
+    lines::{sum_lines, Document},
+    report::Report,
+};
+use std::{
+    env,
+    fs::File,
+    io::{self, BufRead, BufWriter, Write},
+    path::Path,
+    time::{Duration, Instant},
+};
+
+const DEFAULT_MIB: u64 = 512;
+
+/// Lines of letters with a few digits in them, like the puzzle input
+fn generate(path: &Path, bytes: u64) -> io::Result<()> {
+    let mut out = BufWriter::new(File::create(path)?);
+    // Any old generator will do, it only has to be repeatable
+    let mut state: u64 = 0x2545_f491_4f6c_dd1d;
+    let mut next = move |below: u64| {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        state % below
+    };
+
+    let mut written = 0;
+    let mut line = Vec::new();
+    while written < bytes {
+        line.clear();
+        for _ in 0..10 + next(40) {
+            let byte = match next(8) {
+                0 => b'0' + next(10) as u8,
+                _ => b'a' + next(26) as u8,
+            };
+            line.push(byte);
+        }
+        line.push(b'\n');
+        out.write_all(&line)?;
+        written += line.len() as u64;
+    }
+    out.flush()
+}
+
+fn first_last(line: &str) -> Option<u64> {
+    let mut digits = line.chars().filter_map(|c| c.to_digit(10));
+    let first = digits.next()?;
+    let last = digits.next_back().unwrap_or(first);
+    Some((first * 10 + last).into())
+}
+
+fn read(number: usize, line: &str, report: &mut Report) -> Option<u64> {
+    let value = first_last(line);
+    if value.is_none() {
+        report.skip(number, "no digits");
+    }
+    value
+}
+
+fn serial(path: &Path) -> u64 {
+    let lines = io::BufReader::new(File::open(path).unwrap()).lines();
+    lines.filter_map(|line| first_last(&line.unwrap())).sum()
+}
+
+fn parallel(path: &Path) -> u64 {
+    let document = Document::open(path).unwrap();
+    sum_lines(document.bytes(), read).0.unwrap()
+}
+
+fn time(name: &str, f: impl FnOnce() -> u64) -> (u64, Duration) {
+    let start = Instant::now();
+    let sum = f();
+    let elapsed = start.elapsed();
+    println!("{name:<24} {sum:>16} {elapsed:>12.3?}");
+    (sum, elapsed)
+}
+
+fn main() {
+    let mib = env::var("LINES_BENCH_MIB")
+        .ok()
+        .and_then(|mib| mib.parse().ok())
+        .unwrap_or(DEFAULT_MIB);
+    let path = env::temp_dir().join(format!("aoc-lines-bench-{mib}.txt"));
+    if !path.exists() {
+        println!("Generating {mib} MiB in {}...", path.display());
+        generate(&path, mib << 20).unwrap();
+    }
+
+    let threads = rayon::current_num_threads();
+    let (expected, baseline) = time("BufRead::lines", || serial(&path));
+    let one = rayon::ThreadPoolBuilder::new()
+        .num_threads(1)
+        .build()
+        .unwrap();
+    let (sum, _) = time("sum_lines, 1 thread", || one.install(|| parallel(&path)));
+    assert_eq!(sum, expected);
+    let (sum, elapsed) = time(&format!("sum_lines, all {threads}"), || parallel(&path));
+    assert_eq!(sum, expected);
+
+    println!(
+        "Speedup over BufRead::lines: {:.2}x",
+        baseline.as_secs_f64() / elapsed.as_secs_f64()
+    );
+}
@@ -0,0 +1,174 @@
+use std::ops::{BitAnd, BitOr};
+
+const WORD_BITS: usize = u128::BITS as usize;
+
+/// A set of small numbers, `0..128 * WORDS`, stored as one bit each
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct BitSet<const WORDS: usize = 1> {
+    words: [u128; WORDS],
+}
+
+impl<const WORDS: usize> Default for BitSet<WORDS> {
+    fn default() -> Self {
+        BitSet { words: [0; WORDS] }
+    }
+}
+
+impl<const WORDS: usize> BitSet<WORDS> {
+    /// Numbers must be below this
+    pub const WIDTH: usize = WORD_BITS * WORDS;
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns whether `n` wasn't already in the set. Panics if `n` is too big for the set
+    pub fn insert(&mut self, n: usize) -> bool {
+        let (word, bit) = Self::locate(n);
+        let added = self.words[word] & bit == 0;
+        self.words[word] |= bit;
+        added
+    }
+
+    /// Returns whether `n` was in the set
+    pub fn remove(&mut self, n: usize) -> bool {
+        if n >= Self::WIDTH {
+            return false;
+        }
+        let (word, bit) = Self::locate(n);
+        let removed = self.words[word] & bit != 0;
+        self.words[word] &= !bit;
+        removed
+    }
+
+    pub fn contains(&self, n: usize) -> bool {
+        n < Self::WIDTH && {
+            let (word, bit) = Self::locate(n);
+            self.words[word] & bit != 0
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.words
+            .iter()
+            .map(|word| word.count_ones() as usize)
+            .sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.iter().all(|word| *word == 0)
+    }
+
+    /// Number of items in both sets, without building the intersection
+    pub fn intersection_count(&self, other: &Self) -> usize {
+        self.words
+            .iter()
+            .zip(other.words.iter())
+            .map(|(a, b)| (a & b).count_ones() as usize)
+            .sum()
+    }
+
+    /// Numbers in the set, smallest first
+    pub fn iter(&self) -> impl Iterator<Item = usize> + '_ {
+        self.words.iter().enumerate().flat_map(|(idx, word)| {
+            let mut word = *word;
+            std::iter::from_fn(move || {
+                (word != 0).then(|| {
+                    let bit = word.trailing_zeros() as usize;
+                    word &= word - 1;
+                    idx * WORD_BITS + bit
+                })
+            })
+        })
+    }
+
+    fn locate(n: usize) -> (usize, u128) {
+        assert!(
+            n < Self::WIDTH,
+            "{n} doesn't fit in a set of width {}",
+            Self::WIDTH
+        );
+        (n / WORD_BITS, 1 << (n % WORD_BITS))
+    }
+}
+
+impl<const WORDS: usize> BitAnd for BitSet<WORDS> {
+    type Output = Self;
+
+    fn bitand(mut self, other: Self) -> Self {
+        for (a, b) in self.words.iter_mut().zip(other.words) {
+            *a &= b;
+        }
+        self
+    }
+}
+
+impl<const WORDS: usize> BitOr for BitSet<WORDS> {
+    type Output = Self;
+
+    fn bitor(mut self, other: Self) -> Self {
+        for (a, b) in self.words.iter_mut().zip(other.words) {
+            *a |= b;
+        }
+        self
+    }
+}
+
+impl<const WORDS: usize> Extend<usize> for BitSet<WORDS> {
+    fn extend<I: IntoIterator<Item = usize>>(&mut self, iter: I) {
+        for n in iter {
+            self.insert(n);
+        }
+    }
+}
+
+impl<const WORDS: usize> FromIterator<usize> for BitSet<WORDS> {
+    fn from_iter<I: IntoIterator<Item = usize>>(iter: I) -> Self {
+        let mut set = Self::new();
+        set.extend(iter);
+        set
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn insert_remove_contains() {
+        let mut set: BitSet<2> = BitSet::new();
+        assert!(set.is_empty());
+        assert!(set.insert(0));
+        assert!(set.insert(127));
+        assert!(set.insert(128));
+        assert!(!set.insert(128));
+        assert!(set.insert(255));
+        assert_eq!(set.len(), 4);
+        assert_eq!(set.iter().collect::<Vec<_>>(), vec![0, 127, 128, 255]);
+
+        assert!(set.remove(127));
+        assert!(!set.remove(127));
+        assert!(!set.remove(1000));
+        assert!(!set.contains(127));
+        assert!(!set.contains(1000));
+        assert!(set.contains(255));
+    }
+
+    #[test]
+    fn intersections() {
+        let winning: BitSet = [41, 48, 83, 86, 17].into_iter().collect();
+        let picks: BitSet = [83, 86, 6, 31, 17, 9, 48, 53].into_iter().collect();
+        assert_eq!(winning.intersection_count(&picks), 4);
+        assert_eq!(
+            (winning & picks).iter().collect::<Vec<_>>(),
+            vec![17, 48, 83, 86]
+        );
+        assert_eq!((winning | picks).len(), 9);
+    }
+
+    #[test]
+    #[should_panic]
+    fn too_wide() {
+        BitSet::<1>::new().insert(128);
+    }
+}
@@ -0,0 +1,63 @@
+//! Sums and products that stop with an error on overflow, where `Iterator::sum` would
+//! quietly wrap in release builds.
+
+use num_traits::{CheckedAdd, CheckedMul, One, Zero};
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Overflow {
+    Sum,
+    Product,
+}
+
+impl fmt::Display for Overflow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Overflow::Sum => write!(f, "sum overflowed"),
+            Overflow::Product => write!(f, "product overflowed"),
+        }
+    }
+}
+
+impl std::error::Error for Overflow {}
+
+pub trait CheckedIterator: Iterator + Sized {
+    /// Like `sum`, but fails instead of overflowing
+    fn checked_sum(mut self) -> Result<Self::Item, Overflow>
+    where
+        Self::Item: CheckedAdd + Zero,
+    {
+        self.try_fold(Self::Item::zero(), |acc, value| acc.checked_add(&value))
+            .ok_or(Overflow::Sum)
+    }
+
+    /// Like `product`, but fails instead of overflowing
+    fn checked_product(self) -> Result<Self::Item, Overflow>
+    where
+        Self::Item: CheckedMul + One,
+    {
+        crate::math::checked_product(self).ok_or(Overflow::Product)
+    }
+}
+
+impl<I: Iterator> CheckedIterator for I {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn overflow_is_an_error() {
+        assert_eq!([200_u8, 55].into_iter().checked_sum(), Ok(255));
+        assert_eq!([200_u8, 56].into_iter().checked_sum(), Err(Overflow::Sum));
+        assert_eq!(std::iter::empty::<u32>().checked_sum(), Ok(0));
+
+        assert_eq!([16_u8, 15].into_iter().checked_product(), Ok(240));
+        assert_eq!(
+            [16_u8, 16].into_iter().checked_product(),
+            Err(Overflow::Product)
+        );
+        assert_eq!(std::iter::empty::<u32>().checked_product(), Ok(1));
+        assert_eq!([-3_i64, 4].into_iter().checked_product(), Ok(-12));
+    }
+}
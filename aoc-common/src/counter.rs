@@ -0,0 +1,256 @@
+use std::{
+    collections::HashMap,
+    hash::Hash,
+    marker::PhantomData,
+    ops::{AddAssign, SubAssign},
+};
+
+/// Items that come from a small fixed set, so they can be counted in an array
+pub trait Alphabet: Sized {
+    /// Position of the item, must be less than the size of the alphabet
+    fn index(&self) -> usize;
+    fn from_index(index: usize) -> Self;
+}
+
+/// Where a `Counter` keeps its counts. Items with a count of 0 are never iterated
+pub trait Backend<T>: Default + Clone {
+    fn get(&self, item: &T) -> usize;
+    fn set(&mut self, item: T, count: usize);
+    fn items(&self) -> impl Iterator<Item = (T, usize)> + '_;
+}
+
+impl<T: Hash + Eq + Clone> Backend<T> for HashMap<T, usize> {
+    fn get(&self, item: &T) -> usize {
+        HashMap::get(self, item).copied().unwrap_or(0)
+    }
+
+    fn set(&mut self, item: T, count: usize) {
+        if count == 0 {
+            self.remove(&item);
+        } else {
+            self.insert(item, count);
+        }
+    }
+
+    fn items(&self) -> impl Iterator<Item = (T, usize)> + '_ {
+        self.iter().map(|(item, count)| (item.clone(), *count))
+    }
+}
+
+/// Stack allocated counts for an alphabet of `N` items
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ArrayBackend<const N: usize>([usize; N]);
+
+impl<const N: usize> Default for ArrayBackend<N> {
+    fn default() -> Self {
+        ArrayBackend([0; N])
+    }
+}
+
+impl<T: Alphabet, const N: usize> Backend<T> for ArrayBackend<N> {
+    fn get(&self, item: &T) -> usize {
+        self.0[item.index()]
+    }
+
+    fn set(&mut self, item: T, count: usize) {
+        self.0[item.index()] = count;
+    }
+
+    fn items(&self) -> impl Iterator<Item = (T, usize)> + '_ {
+        self.0
+            .iter()
+            .enumerate()
+            .filter(|(_, count)| **count > 0)
+            .map(|(index, count)| (T::from_index(index), *count))
+    }
+}
+
+/// Counts how many times each item appears, a multiset
+#[derive(Debug, Clone)]
+pub struct Counter<T, B = HashMap<T, usize>> {
+    counts: B,
+    item: PhantomData<T>,
+}
+
+/// A `Counter` for tiny alphabets (e.g. the 13 card ranks) that never allocates
+pub type SmallCounter<T, const N: usize> = Counter<T, ArrayBackend<N>>;
+
+impl<T, B: Backend<T>> Default for Counter<T, B> {
+    fn default() -> Self {
+        Counter {
+            counts: B::default(),
+            item: PhantomData,
+        }
+    }
+}
+
+impl<T, B: Backend<T>> Counter<T, B> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn get(&self, item: &T) -> usize {
+        self.counts.get(item)
+    }
+
+    /// Adds one of `item`
+    pub fn add(&mut self, item: T) {
+        self.add_n(item, 1);
+    }
+
+    pub fn add_n(&mut self, item: T, n: usize) {
+        let count = self.counts.get(&item) + n;
+        self.counts.set(item, count);
+    }
+
+    /// Removes up to `n` of `item`, counts never go below 0
+    pub fn subtract_n(&mut self, item: T, n: usize) {
+        let count = self.counts.get(&item).saturating_sub(n);
+        self.counts.set(item, count);
+    }
+
+    /// Removes every one of `item`, returning how many there were
+    pub fn remove(&mut self, item: T) -> usize {
+        let count = self.counts.get(&item);
+        self.counts.set(item, 0);
+        count
+    }
+
+    /// Keeps the larger count of each item, like a union of the two multisets
+    pub fn merge(&mut self, other: &Self) {
+        for (item, count) in other.counts.items() {
+            if count > self.counts.get(&item) {
+                self.counts.set(item, count);
+            }
+        }
+    }
+
+    /// Items and their counts, in no particular order
+    pub fn iter(&self) -> impl Iterator<Item = (T, usize)> + '_ {
+        self.counts.items()
+    }
+
+    /// Number of different items
+    pub fn len(&self) -> usize {
+        self.counts.items().count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.counts.items().next().is_none()
+    }
+
+    /// Number of items counting repeats
+    pub fn total(&self) -> usize {
+        self.counts.items().map(|(_, count)| count).sum()
+    }
+
+    /// Items from most to least common. Ties keep the backend's order
+    pub fn most_common(&self) -> Vec<(T, usize)> {
+        let mut items: Vec<(T, usize)> = self.counts.items().collect();
+        items.sort_by_key(|(_, count)| std::cmp::Reverse(*count));
+        items
+    }
+
+    /// Just the counts from most to least common, e.g. `[3, 2]` for a full house
+    pub fn signature(&self) -> Vec<usize> {
+        self.most_common()
+            .into_iter()
+            .map(|(_, count)| count)
+            .collect()
+    }
+}
+
+impl<T, B: Backend<T>> AddAssign<&Self> for Counter<T, B> {
+    fn add_assign(&mut self, other: &Self) {
+        for (item, count) in other.counts.items() {
+            self.add_n(item, count);
+        }
+    }
+}
+
+impl<T, B: Backend<T>> SubAssign<&Self> for Counter<T, B> {
+    fn sub_assign(&mut self, other: &Self) {
+        for (item, count) in other.counts.items() {
+            self.subtract_n(item, count);
+        }
+    }
+}
+
+impl<T, B: Backend<T>> Extend<T> for Counter<T, B> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for item in iter {
+            self.add(item);
+        }
+    }
+}
+
+impl<T, B: Backend<T>> FromIterator<T> for Counter<T, B> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut counter = Self::new();
+        counter.extend(iter);
+        counter
+    }
+}
+
+impl<T, B: Backend<T>> FromIterator<(T, usize)> for Counter<T, B> {
+    fn from_iter<I: IntoIterator<Item = (T, usize)>>(iter: I) -> Self {
+        let mut counter = Self::new();
+        for (item, n) in iter {
+            counter.add_n(item, n);
+        }
+        counter
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    struct Digit(u8);
+
+    impl Alphabet for Digit {
+        fn index(&self) -> usize {
+            self.0 as usize
+        }
+        fn from_index(index: usize) -> Self {
+            Digit(index as u8)
+        }
+    }
+
+    #[test]
+    fn most_common_and_signature() {
+        let counter: Counter<char> = "abracadabra".chars().collect();
+        assert_eq!(counter.most_common()[0], ('a', 5));
+        assert_eq!(counter.signature(), vec![5, 2, 2, 1, 1]);
+        assert_eq!(counter.total(), 11);
+
+        let counter: SmallCounter<Digit, 10> = [3, 1, 3, 1, 3].map(Digit).into_iter().collect();
+        assert_eq!(counter.most_common(), vec![(Digit(3), 3), (Digit(1), 2)]);
+        assert_eq!(counter.signature(), vec![3, 2]);
+    }
+
+    #[test]
+    fn add_subtract_merge() {
+        let mut a: Counter<&str> = [("red", 3), ("blue", 1)].into_iter().collect();
+        let b: Counter<&str> = [("red", 1), ("green", 2)].into_iter().collect();
+
+        let mut merged = a.clone();
+        merged.merge(&b);
+        assert_eq!(
+            [
+                merged.get(&"red"),
+                merged.get(&"blue"),
+                merged.get(&"green")
+            ],
+            [3, 1, 2]
+        );
+
+        a += &b;
+        assert_eq!([a.get(&"red"), a.get(&"blue"), a.get(&"green")], [4, 1, 2]);
+
+        a -= &merged;
+        assert_eq!(a.most_common(), vec![("red", 1)]);
+        assert_eq!(a.len(), 1);
+    }
+}
@@ -0,0 +1,211 @@
+//! Reads tokens straight out of the input bytes, nothing is copied or allocated unless
+//! something goes wrong.
+
+use num_traits::PrimInt;
+use std::fmt;
+
+/// Line and column of a byte in the input, both counted from 1
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Position {
+    pub line: usize,
+    pub col: usize,
+}
+
+impl fmt::Display for Position {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.line, self.col)
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum ErrKind {
+    ExpectedNumber,
+    Overflow,
+    ExpectedLiteral(String),
+}
+
+#[derive(Debug, PartialEq)]
+pub struct CursorErr {
+    pub kind: ErrKind,
+    pub position: Position,
+}
+
+impl fmt::Display for CursorErr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.kind {
+            ErrKind::ExpectedNumber => write!(f, "{}: expected a number", self.position),
+            ErrKind::Overflow => write!(f, "{}: number is too big", self.position),
+            ErrKind::ExpectedLiteral(literal) => {
+                write!(f, "{}: expected {literal:?}", self.position)
+            }
+        }
+    }
+}
+
+impl std::error::Error for CursorErr {}
+
+#[derive(Debug, Clone)]
+pub struct Cursor<'a> {
+    input: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> Cursor<'a> {
+    pub fn new(input: &'a [u8]) -> Self {
+        Cursor { input, offset: 0 }
+    }
+
+    /// Bytes read so far
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// Where the cursor is, for error messages. Counts lines so don't call it in a hot loop
+    pub fn position(&self) -> Position {
+        let read = &self.input[..self.offset];
+        let line_start = read
+            .iter()
+            .rposition(|byte| *byte == b'\n')
+            .map_or(0, |idx| idx + 1);
+        Position {
+            line: read.iter().filter(|byte| **byte == b'\n').count() + 1,
+            col: self.offset - line_start + 1,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.offset == self.input.len()
+    }
+
+    /// Everything not read yet
+    pub fn rest(&self) -> &'a [u8] {
+        &self.input[self.offset..]
+    }
+
+    pub fn peek(&self) -> Option<u8> {
+        self.input.get(self.offset).copied()
+    }
+
+    pub fn next_byte(&mut self) -> Option<u8> {
+        let byte = self.peek()?;
+        self.offset += 1;
+        Some(byte)
+    }
+
+    /// Reads bytes as long as `f` is true for them
+    pub fn take_while(&mut self, mut f: impl FnMut(u8) -> bool) -> &'a [u8] {
+        let start = self.offset;
+        let len = self.rest().iter().take_while(|byte| f(**byte)).count();
+        self.offset += len;
+        &self.input[start..self.offset]
+    }
+
+    pub fn skip_while(&mut self, f: impl FnMut(u8) -> bool) {
+        self.take_while(f);
+    }
+
+    /// Skips spaces and tabs but not newlines
+    pub fn skip_spaces(&mut self) {
+        self.skip_while(|byte| byte == b' ' || byte == b'\t');
+    }
+
+    /// Moves to the next `byte`, leaving it unread. Returns false and moves to the end
+    /// if there isn't one
+    pub fn skip_until(&mut self, byte: u8) -> bool {
+        self.skip_while(|next| next != byte);
+        !self.is_empty()
+    }
+
+    /// Reads the rest of the line without the line ending, or `None` at the end of the input
+    pub fn next_line(&mut self) -> Option<&'a [u8]> {
+        if self.is_empty() {
+            return None;
+        }
+        let line = self.take_while(|byte| byte != b'\n');
+        self.next_byte();
+        Some(line.strip_suffix(b"\r").unwrap_or(line))
+    }
+
+    /// Reads `literal`, or leaves the cursor where it was if the input doesn't start with it
+    pub fn expect_literal(&mut self, literal: &str) -> Result<(), CursorErr> {
+        if self.rest().starts_with(literal.as_bytes()) {
+            self.offset += literal.len();
+            Ok(())
+        } else {
+            Err(self.error(ErrKind::ExpectedLiteral(literal.to_string())))
+        }
+    }
+
+    /// Skips spaces then reads a run of digits, leaving the cursor where it was on failure
+    pub fn next_uint<T: PrimInt>(&mut self) -> Result<T, CursorErr> {
+        let start = self.offset;
+        self.skip_spaces();
+        let digits_start = self.offset;
+        let digits = self.take_while(|byte| byte.is_ascii_digit());
+
+        let value = if digits.is_empty() {
+            Err(ErrKind::ExpectedNumber)
+        } else {
+            let ten = T::from(10).unwrap();
+            digits.iter().try_fold(T::zero(), |acc, digit| {
+                acc.checked_mul(&ten)
+                    .and_then(|acc| acc.checked_add(&T::from(digit - b'0').unwrap()))
+                    .ok_or(ErrKind::Overflow)
+            })
+        };
+
+        value.map_err(|kind| {
+            self.offset = digits_start;
+            let err = self.error(kind);
+            self.offset = start;
+            err
+        })
+    }
+
+    fn error(&self, kind: ErrKind) -> CursorErr {
+        CursorErr {
+            kind,
+            position: self.position(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn read_card() {
+        let mut cursor = Cursor::new(b"Card  1: 41 48 | 83 86\nCard  2: 13");
+        cursor.expect_literal("Card").unwrap();
+        assert_eq!(cursor.next_uint::<u8>(), Ok(1));
+        cursor.expect_literal(":").unwrap();
+        assert_eq!(cursor.next_uint::<u32>(), Ok(41));
+        assert!(cursor.skip_until(b'|'));
+        cursor.next_byte();
+        assert_eq!(cursor.next_line(), Some(&b" 83 86"[..]));
+        assert_eq!(cursor.next_line(), Some(&b"Card  2: 13"[..]));
+        assert_eq!(cursor.next_line(), None);
+        assert!(!cursor.skip_until(b'|'));
+    }
+
+    #[test]
+    fn errors_report_positions() {
+        let mut cursor = Cursor::new(b"seeds: 79\nsoil 300");
+        cursor.next_line();
+        let err = cursor.expect_literal("seeds:").unwrap_err();
+        assert_eq!(err.position, Position { line: 2, col: 1 });
+        assert_eq!(err.to_string(), "2:1: expected \"seeds:\"");
+
+        cursor.expect_literal("soil").unwrap();
+        let err = cursor.next_uint::<u8>().unwrap_err();
+        assert_eq!(err.kind, ErrKind::Overflow);
+        assert_eq!(err.position, Position { line: 2, col: 6 });
+        // Nothing was read
+        assert_eq!(cursor.next_uint::<u16>(), Ok(300));
+        assert_eq!(
+            cursor.next_uint::<u16>().unwrap_err().kind,
+            ErrKind::ExpectedNumber
+        );
+    }
+}
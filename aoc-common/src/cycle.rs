@@ -0,0 +1,152 @@
+//! Finds where a sequence of states, each made from the one before by a step function,
+//! starts repeating.
+
+use std::{collections::HashMap, hash::Hash};
+
+/// The sequence repeats every `period` states once it reaches the state at index `start`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cycle {
+    pub start: usize,
+    pub period: usize,
+}
+
+impl Cycle {
+    /// Index of a state before the first repeat that is the same as the state at `n`
+    pub fn reduce(&self, n: usize) -> usize {
+        if n < self.start {
+            n
+        } else {
+            self.start + (n - self.start) % self.period
+        }
+    }
+}
+
+/// Brent's algorithm, only keeps two states around at once but steps through the
+/// sequence a few times. Never returns if the sequence doesn't repeat
+pub fn brent<S: PartialEq + Clone>(start: S, mut step: impl FnMut(&S) -> S) -> Cycle {
+    // Find the period, the tortoise waits at each power of two for the hare to come round
+    let mut power = 1;
+    let mut period = 1;
+    let mut tortoise = start.clone();
+    let mut hare = step(&start);
+    while tortoise != hare {
+        if power == period {
+            tortoise = hare.clone();
+            power *= 2;
+            period = 0;
+        }
+        hare = step(&hare);
+        period += 1;
+    }
+
+    // With the hare a period ahead, they first meet at the start of the cycle
+    let mut tortoise = start.clone();
+    let mut hare = start;
+    for _ in 0..period {
+        hare = step(&hare);
+    }
+    let mut cycle_start = 0;
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        cycle_start += 1;
+    }
+
+    Cycle {
+        start: cycle_start,
+        period,
+    }
+}
+
+/// Steps through the sequence once, hashing every state. Returns the cycle and every state
+/// before the first repeat. Never returns if the sequence doesn't repeat
+pub fn detect<S: Hash + Eq + Clone>(start: S, step: impl FnMut(&S) -> S) -> (Cycle, Vec<S>) {
+    match walk(start, step, None) {
+        Walk::Repeats(cycle, states) => (cycle, states),
+        Walk::Reached(_) => unreachable!("walk only stops early with a limit"),
+    }
+}
+
+/// The state at index `n`, skipping over whole cycles instead of stepping through them
+pub fn nth<S: Hash + Eq + Clone>(start: S, step: impl FnMut(&S) -> S, n: usize) -> S {
+    match walk(start, step, Some(n)) {
+        Walk::Repeats(cycle, mut states) => states.swap_remove(cycle.reduce(n)),
+        Walk::Reached(state) => state,
+    }
+}
+
+enum Walk<S> {
+    Repeats(Cycle, Vec<S>),
+    /// Got to the limit before anything repeated
+    Reached(S),
+}
+
+fn walk<S: Hash + Eq + Clone>(
+    start: S,
+    mut step: impl FnMut(&S) -> S,
+    limit: Option<usize>,
+) -> Walk<S> {
+    let mut seen = HashMap::new();
+    let mut states = Vec::new();
+    let mut state = start;
+
+    loop {
+        let index = states.len();
+        if let Some(first) = seen.get(&state) {
+            let cycle = Cycle {
+                start: *first,
+                period: index - first,
+            };
+            return Walk::Repeats(cycle, states);
+        }
+        if limit == Some(index) {
+            return Walk::Reached(state);
+        }
+
+        let next = step(&state);
+        seen.insert(state.clone(), index);
+        states.push(state);
+        state = next;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn step(x: &u64) -> u64 {
+        (x * x + 1) % 2003
+    }
+
+    #[test]
+    fn brent_and_hashing_agree() {
+        for start in [0, 3, 1000, 1999] {
+            let (cycle, states) = detect(start, step);
+            assert_eq!(brent(start, step), cycle);
+            assert_eq!(states.len(), cycle.start + cycle.period);
+            assert_eq!(step(states.last().unwrap()), states[cycle.start]);
+        }
+
+        // Already looping from the first state
+        assert_eq!(
+            brent(2, |x| (x + 1) % 5),
+            Cycle {
+                start: 0,
+                period: 5
+            }
+        );
+    }
+
+    #[test]
+    fn nth_skips_cycles() {
+        let mut slow = 7;
+        for n in 0..500 {
+            assert_eq!(nth(7, step, n), slow);
+            slow = step(&slow);
+        }
+
+        let (cycle, _) = detect(7, step);
+        let far = 1_000_000_000;
+        assert_eq!(nth(7, step, far), nth(7, step, cycle.reduce(far)));
+    }
+}
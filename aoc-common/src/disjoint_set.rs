@@ -0,0 +1,242 @@
+use std::{collections::HashMap, hash::Hash};
+
+/// Union-find over keys, which can be indexes (see `with_len`) or anything hashable.
+/// Keys are added as their own component the first time they're seen
+#[derive(Debug, Clone)]
+pub struct DisjointSet<K = usize> {
+    slots: HashMap<K, usize>,
+    keys: Vec<K>,
+    parents: Vec<usize>,
+    ranks: Vec<u8>,
+    sizes: Vec<usize>,
+    components: usize,
+}
+
+impl<K> Default for DisjointSet<K> {
+    fn default() -> Self {
+        DisjointSet {
+            slots: HashMap::new(),
+            keys: Vec::new(),
+            parents: Vec::new(),
+            ranks: Vec::new(),
+            sizes: Vec::new(),
+            components: 0,
+        }
+    }
+}
+
+impl DisjointSet<usize> {
+    /// The indexes `0..len`, each on its own
+    pub fn with_len(len: usize) -> Self {
+        (0..len).collect()
+    }
+}
+
+impl<K: Hash + Eq + Clone> DisjointSet<K> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds `key` on its own, returns false if it was already there
+    pub fn insert(&mut self, key: K) -> bool {
+        if self.slots.contains_key(&key) {
+            return false;
+        }
+        self.slot(key);
+        true
+    }
+
+    /// Number of keys
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    pub fn contains(&self, key: &K) -> bool {
+        self.slots.contains_key(key)
+    }
+
+    /// Number of separate components
+    pub fn components_count(&self) -> usize {
+        self.components
+    }
+
+    /// The key that stands for the component `key` is in
+    pub fn find(&mut self, key: &K) -> Option<K> {
+        let slot = *self.slots.get(key)?;
+        let root = self.root(slot);
+        Some(self.keys[root].clone())
+    }
+
+    /// Joins the components of `a` and `b`, adding either if it's new.
+    /// Returns false if they were already joined
+    pub fn union(&mut self, a: K, b: K) -> bool {
+        let a = self.slot(a);
+        let b = self.slot(b);
+        let (mut a, mut b) = (self.root(a), self.root(b));
+        if a == b {
+            return false;
+        }
+
+        // Hang the shallower tree off the deeper one
+        if self.ranks[a] < self.ranks[b] {
+            (a, b) = (b, a);
+        }
+        self.parents[b] = a;
+        self.sizes[a] += self.sizes[b];
+        if self.ranks[a] == self.ranks[b] {
+            self.ranks[a] += 1;
+        }
+        self.components -= 1;
+        true
+    }
+
+    pub fn same(&mut self, a: &K, b: &K) -> bool {
+        match (self.slots.get(a), self.slots.get(b)) {
+            (Some(a), Some(b)) => {
+                let (a, b) = (*a, *b);
+                self.root(a) == self.root(b)
+            }
+            _ => false,
+        }
+    }
+
+    /// Number of keys in the component `key` is in, 0 if it isn't there
+    pub fn size(&mut self, key: &K) -> usize {
+        match self.slots.get(key) {
+            Some(slot) => {
+                let root = self.root(*slot);
+                self.sizes[root]
+            }
+            None => 0,
+        }
+    }
+
+    /// The keys in each component. Components and their keys are in the order they were added
+    pub fn components(&mut self) -> Vec<Vec<K>> {
+        let mut groups: Vec<Vec<K>> = Vec::new();
+        let mut group_of_root = HashMap::new();
+        for slot in 0..self.keys.len() {
+            let root = self.root(slot);
+            let group = *group_of_root.entry(root).or_insert_with(|| {
+                groups.push(Vec::new());
+                groups.len() - 1
+            });
+            groups[group].push(self.keys[slot].clone());
+        }
+        groups
+    }
+
+    fn slot(&mut self, key: K) -> usize {
+        if let Some(slot) = self.slots.get(&key) {
+            return *slot;
+        }
+
+        let slot = self.keys.len();
+        self.slots.insert(key.clone(), slot);
+        self.keys.push(key);
+        self.parents.push(slot);
+        self.ranks.push(0);
+        self.sizes.push(1);
+        self.components += 1;
+        slot
+    }
+
+    fn root(&mut self, slot: usize) -> usize {
+        let mut root = slot;
+        while self.parents[root] != root {
+            root = self.parents[root];
+        }
+
+        // Path compression, point everything on the way straight at the root
+        let mut slot = slot;
+        while self.parents[slot] != root {
+            let parent = self.parents[slot];
+            self.parents[slot] = root;
+            slot = parent;
+        }
+        root
+    }
+}
+
+impl<K: Hash + Eq + Clone> Extend<K> for DisjointSet<K> {
+    fn extend<I: IntoIterator<Item = K>>(&mut self, iter: I) {
+        for key in iter {
+            self.insert(key);
+        }
+    }
+}
+
+impl<K: Hash + Eq + Clone> FromIterator<K> for DisjointSet<K> {
+    fn from_iter<I: IntoIterator<Item = K>>(iter: I) -> Self {
+        let mut set = Self::new();
+        set.extend(iter);
+        set
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn union_by_index() {
+        let mut set = DisjointSet::with_len(6);
+        assert_eq!(set.components_count(), 6);
+        assert!(set.union(0, 1));
+        assert!(set.union(2, 3));
+        assert!(set.union(1, 3));
+        assert!(!set.union(0, 2));
+
+        assert!(set.same(&0, &3));
+        assert!(!set.same(&0, &4));
+        assert!(!set.same(&0, &9));
+        assert_eq!(set.find(&2), set.find(&1));
+        assert_eq!((set.size(&2), set.size(&5), set.size(&9)), (4, 1, 0));
+        assert_eq!(set.components_count(), 3);
+        assert_eq!(set.components(), vec![vec![0, 1, 2, 3], vec![4], vec![5]]);
+    }
+
+    #[test]
+    fn find_compresses_paths() {
+        // Equal ranks all the way up, so 7 ends up three links below the root
+        let mut set = DisjointSet::with_len(8);
+        for (a, b) in [(0, 1), (2, 3), (4, 5), (6, 7), (0, 2), (4, 6), (0, 4)] {
+            set.union(a, b);
+        }
+        let root = set.find(&0).unwrap();
+        assert_eq!(set.parents[6], 4);
+        assert_eq!(set.parents[7], 6);
+
+        assert_eq!(set.find(&7), Some(root));
+        assert_eq!(set.parents[7], root);
+        assert_eq!(set.parents[6], root);
+        assert_eq!(set.parents[4], root);
+    }
+
+    #[test]
+    fn union_by_name() {
+        let wires = "jqt-rhn jqt-xhk jqt-nvd rsh-frs rsh-pzl rsh-lsr xhk-hfx cmg-qnr cmg-nvd \
+                     cmg-lhk cmg-bvb rhn-xhk rhn-bvb rhn-hfx bvb-xhk bvb-hfx pzl-lsr pzl-hfx \
+                     pzl-nvd qnr-nvd ntq-jqt ntq-hfx ntq-bvb ntq-xhk nvd-lhk lsr-lhk rzs-qnr \
+                     rzs-cmg rzs-lsr rzs-rsh frs-qnr frs-lhk frs-lsr";
+        // The three wires that split the machine in two
+        let cut = ["pzl-hfx", "cmg-bvb", "jqt-nvd"];
+
+        let mut set = DisjointSet::new();
+        for wire in wires.split_whitespace().filter(|wire| !cut.contains(wire)) {
+            let (a, b) = wire.split_once('-').unwrap();
+            set.union(a, b);
+        }
+
+        assert_eq!(set.len(), 15);
+        assert_eq!(set.components_count(), 2);
+        let mut sizes: Vec<usize> = set.components().iter().map(|c| c.len()).collect();
+        sizes.sort();
+        assert_eq!(sizes, vec![6, 9]);
+        assert_eq!(set.size(&"cmg"), 9);
+    }
+}
@@ -0,0 +1,299 @@
+//! Polygons and lines on integer points. Anything that can land between lattice points is
+//! worked out exactly with `Rational`.
+
+use crate::{
+    math::gcd,
+    point::{Point, Point3},
+    rational::Rational,
+};
+
+/// Where a point is relative to a polygon
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Location {
+    Inside,
+    Boundary,
+    Outside,
+}
+
+/// Each edge of the polygon, including the one from the last vertex back to the first
+fn edges(polygon: &[Point]) -> impl Iterator<Item = (Point, Point)> + '_ {
+    polygon
+        .iter()
+        .copied()
+        .zip(polygon.iter().copied().cycle().skip(1))
+}
+
+fn cross(a: Point, b: Point) -> i128 {
+    a.row as i128 * b.col as i128 - a.col as i128 * b.row as i128
+}
+
+/// Twice the area of the polygon, which is always a whole number. The vertices can go round
+/// either way but the edges mustn't cross
+pub fn twice_area(polygon: &[Point]) -> i128 {
+    // Shoelace formula
+    edges(polygon).map(|(a, b)| cross(a, b)).sum::<i128>().abs()
+}
+
+pub fn area(polygon: &[Point]) -> Rational {
+    Rational::new(twice_area(polygon), 2)
+}
+
+/// Lattice points on the edges of the polygon
+pub fn boundary_points(polygon: &[Point]) -> i128 {
+    edges(polygon)
+        .map(|(a, b)| gcd(a.row.abs_diff(b.row), a.col.abs_diff(b.col)) as i128)
+        .sum()
+}
+
+/// Lattice points strictly inside the polygon, from Pick's theorem `A = I + B/2 - 1`
+pub fn interior_points(polygon: &[Point]) -> i128 {
+    (twice_area(polygon) - boundary_points(polygon) + 2) / 2
+}
+
+/// Lattice points inside or on the polygon, e.g. the tiles of a lagoon dug round its edge
+pub fn lattice_points(polygon: &[Point]) -> i128 {
+    interior_points(polygon) + boundary_points(polygon)
+}
+
+fn on_segment(point: Point, a: Point, b: Point) -> bool {
+    cross(b - a, point - a) == 0
+        && (a.row.min(b.row)..=a.row.max(b.row)).contains(&point.row)
+        && (a.col.min(b.col)..=a.col.max(b.col)).contains(&point.col)
+}
+
+/// Point in polygon test, casting a ray along the row towards increasing columns
+pub fn locate(polygon: &[Point], point: Point) -> Location {
+    let mut inside = false;
+    for (a, b) in edges(polygon) {
+        if on_segment(point, a, b) {
+            return Location::Boundary;
+        }
+
+        // Edges count as covering their lower row but not their upper one, so a ray through
+        // a vertex is only counted once
+        if (a.row > point.row) != (b.row > point.row) {
+            // Sign of how far right of the point the edge crosses its row, scaled by the row step
+            let d = b - a;
+            let right = (a.col - point.col) as i128 * d.row as i128
+                + (point.row - a.row) as i128 * d.col as i128;
+            if right.signum() == d.row.signum() as i128 {
+                inside = !inside;
+            }
+        }
+    }
+
+    if inside {
+        Location::Inside
+    } else {
+        Location::Outside
+    }
+}
+
+/// Whether the two segments share any point, including touching ends
+pub fn segments_intersect((a, b): (Point, Point), (c, d): (Point, Point)) -> bool {
+    let side = |p: Point, q: Point, r: Point| cross(q - p, r - p).signum();
+    let (ab_c, ab_d) = (side(a, b, c), side(a, b, d));
+    let (cd_a, cd_b) = (side(c, d, a), side(c, d, b));
+
+    (ab_c * ab_d < 0 && cd_a * cd_b < 0)
+        || on_segment(c, a, b)
+        || on_segment(d, a, b)
+        || on_segment(a, c, d)
+        || on_segment(b, c, d)
+}
+
+/// The points `origin + direction * t` for every `t`, the direction mustn't be zero
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Line<P> {
+    pub origin: P,
+    pub direction: P,
+}
+
+/// How two lines meet
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Intersection {
+    /// At `t` along the first line and `u` along the second
+    At { t: Rational, u: Rational },
+    /// They're the same line
+    Coincident,
+    /// Parallel, or skew in 3D
+    Never,
+}
+
+impl<P> Line<P> {
+    pub fn new(origin: P, direction: P) -> Self {
+        Line { origin, direction }
+    }
+}
+
+impl Line<Point> {
+    pub fn through(a: Point, b: Point) -> Self {
+        Line::new(a, b - a)
+    }
+
+    /// Row and column of the point `t` along the line
+    pub fn at(&self, t: Rational) -> (Rational, Rational) {
+        (
+            Rational::from(self.origin.row) + Rational::from(self.direction.row) * t,
+            Rational::from(self.origin.col) + Rational::from(self.direction.col) * t,
+        )
+    }
+
+    pub fn intersect(&self, other: &Line<Point>) -> Intersection {
+        let offset = other.origin - self.origin;
+        let denom = cross(self.direction, other.direction);
+        if denom == 0 {
+            return if cross(offset, self.direction) == 0 {
+                Intersection::Coincident
+            } else {
+                Intersection::Never
+            };
+        }
+
+        Intersection::At {
+            t: Rational::new(cross(offset, other.direction), denom),
+            u: Rational::new(cross(offset, self.direction), denom),
+        }
+    }
+}
+
+fn coords(p: Point3) -> [i128; 3] {
+    [p.x as i128, p.y as i128, p.z as i128]
+}
+
+fn cross3(a: [i128; 3], b: [i128; 3]) -> [i128; 3] {
+    [
+        a[1] * b[2] - a[2] * b[1],
+        a[2] * b[0] - a[0] * b[2],
+        a[0] * b[1] - a[1] * b[0],
+    ]
+}
+
+fn dot3(a: [i128; 3], b: [i128; 3]) -> i128 {
+    a.iter().zip(b).map(|(a, b)| a * b).sum()
+}
+
+impl Line<Point3> {
+    pub fn through(a: Point3, b: Point3) -> Self {
+        Line::new(a, b - a)
+    }
+
+    /// x, y and z of the point `t` along the line
+    pub fn at(&self, t: Rational) -> [Rational; 3] {
+        let origin = coords(self.origin);
+        let direction = coords(self.direction);
+        [0, 1, 2].map(|axis| Rational::from(origin[axis]) + Rational::from(direction[axis]) * t)
+    }
+
+    pub fn intersect(&self, other: &Line<Point3>) -> Intersection {
+        let offset = coords(other.origin - self.origin);
+        let (d1, d2) = (coords(self.direction), coords(other.direction));
+        let normal = cross3(d1, d2);
+
+        if normal == [0; 3] {
+            return if cross3(offset, d1) == [0; 3] {
+                Intersection::Coincident
+            } else {
+                Intersection::Never
+            };
+        }
+        if dot3(offset, normal) != 0 {
+            // Not in the same plane
+            return Intersection::Never;
+        }
+
+        let denom = dot3(normal, normal);
+        Intersection::At {
+            t: Rational::new(dot3(cross3(offset, d2), normal), denom),
+            u: Rational::new(dot3(cross3(offset, d1), normal), denom),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dig_plan_area() {
+        let plan = "R6 D5 L2 D2 R2 D2 L5 U2 L1 U2 R2 U3 L2 U2";
+        let mut corner = Point::default();
+        let mut polygon = Vec::new();
+        for step in plan.split(' ') {
+            let direction = match &step[..1] {
+                "R" => Point::RIGHT,
+                "D" => Point::DOWN,
+                "L" => Point::LEFT,
+                _ => Point::UP,
+            };
+            corner = corner + direction * step[1..].parse::<i64>().unwrap();
+            polygon.push(corner);
+        }
+
+        assert_eq!(area(&polygon), Rational::from(42_i64));
+        assert_eq!(boundary_points(&polygon), 38);
+        assert_eq!(interior_points(&polygon), 24);
+        assert_eq!(lattice_points(&polygon), 62);
+    }
+
+    #[test]
+    fn locate_in_concave_polygon() {
+        // A C shape opening to the bottom right
+        let polygon = [
+            (0, 0),
+            (0, 6),
+            (6, 6),
+            (6, 4),
+            (2, 4),
+            (2, 2),
+            (6, 2),
+            (6, 0),
+        ]
+        .map(Point::from);
+        assert_eq!(locate(&polygon, Point::new(2, 1)), Location::Inside);
+        assert_eq!(locate(&polygon, Point::new(1, 3)), Location::Inside);
+        assert_eq!(locate(&polygon, Point::new(4, 3)), Location::Outside);
+        assert_eq!(locate(&polygon, Point::new(0, 7)), Location::Outside);
+        assert_eq!(locate(&polygon, Point::new(2, 3)), Location::Boundary);
+        assert_eq!(locate(&polygon, Point::new(6, 6)), Location::Boundary);
+
+        let touching = (Point::new(2, 2), Point::new(2, 9));
+        assert!(segments_intersect(
+            (Point::new(0, 5), Point::new(2, 5)),
+            touching
+        ));
+        assert!(!segments_intersect(
+            (Point::new(0, 5), Point::new(1, 5)),
+            touching
+        ));
+    }
+
+    #[test]
+    fn line_intersections() {
+        // Hailstones crossing in the future
+        let a = Line::new(Point::new(19, 13), Point::new(-2, 1));
+        let b = Line::new(Point::new(18, 19), Point::new(-1, -1));
+        let Intersection::At { t, u } = a.intersect(&b) else {
+            panic!("hailstones should cross");
+        };
+        assert_eq!(t, Rational::new(7, 3));
+        assert_eq!(a.at(t), (Rational::new(43, 3), Rational::new(46, 3)));
+        assert_eq!(a.at(t), b.at(u));
+
+        let parallel = Line::new(Point::new(20, 25), Point::new(-2, -2));
+        assert_eq!(b.intersect(&parallel), Intersection::Never);
+
+        let x = Line::new(Point3::new(0, 0, 0), Point3::new(1, 1, 1));
+        let y = Line::new(Point3::new(2, 0, 0), Point3::new(-1, 1, 1));
+        let Intersection::At { t, u } = x.intersect(&y) else {
+            panic!("lines should meet");
+        };
+        assert_eq!(x.at(t), [Rational::ONE; 3]);
+        assert_eq!(y.at(u), [Rational::ONE; 3]);
+
+        let skew = Line::new(Point3::new(0, 1, 1), Point3::new(0, 1, 0));
+        assert_eq!(x.intersect(&skew), Intersection::Never);
+        let same = Line::<Point3>::through(Point3::new(2, 2, 2), Point3::new(4, 4, 4));
+        assert_eq!(x.intersect(&same), Intersection::Coincident);
+    }
+}
@@ -0,0 +1,451 @@
+use crate::point::Point;
+use std::{
+    collections::hash_map::DefaultHasher,
+    hash::{Hash, Hasher},
+    ops::{Index, IndexMut},
+    str::FromStr,
+};
+
+/// A rectangle of cells, stored row by row
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    rows: usize,
+    cols: usize,
+    cells: Vec<T>,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct ParseGridErr;
+
+impl<T> Grid<T> {
+    /// Panics if `cells` doesn't hold exactly `rows * cols` cells
+    pub fn new(rows: usize, cols: usize, cells: Vec<T>) -> Self {
+        assert_eq!(
+            cells.len(),
+            rows * cols,
+            "grid has the wrong number of cells"
+        );
+        Grid { rows, cols, cells }
+    }
+
+    /// Parses each line as a row, converting every char with `f`
+    pub fn parse(s: &str, mut f: impl FnMut(char) -> T) -> Result<Self, ParseGridErr> {
+        let mut rows = 0;
+        let mut cols = 0;
+        let mut cells = Vec::new();
+        for line in s.lines() {
+            let before = cells.len();
+            cells.extend(line.chars().map(&mut f));
+            let len = cells.len() - before;
+            if rows == 0 {
+                cols = len;
+            } else if len != cols {
+                // Lines aren't all the same length
+                return Err(ParseGridErr);
+            }
+            rows += 1;
+        }
+        Ok(Grid { rows, cols, cells })
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    pub fn contains(&self, point: Point) -> bool {
+        (0..self.rows as i64).contains(&point.row) && (0..self.cols as i64).contains(&point.col)
+    }
+
+    pub fn get(&self, point: Point) -> Option<&T> {
+        self.index_of(point).map(|idx| &self.cells[idx])
+    }
+
+    pub fn get_mut(&mut self, point: Point) -> Option<&mut T> {
+        self.index_of(point).map(|idx| &mut self.cells[idx])
+    }
+
+    /// Every point in the grid, row by row
+    pub fn points(&self) -> impl Iterator<Item = Point> {
+        let cols = self.cols;
+        (0..self.rows * cols).map(move |idx| Point::new((idx / cols) as i64, (idx % cols) as i64))
+    }
+
+    /// Every point and the cell there, row by row
+    pub fn iter(&self) -> impl Iterator<Item = (Point, &T)> {
+        self.points().zip(self.cells.iter())
+    }
+
+    /// The orthogonal neighbours of `point` that are inside the grid
+    pub fn neighbours(&self, point: Point) -> impl Iterator<Item = Point> + '_ {
+        point
+            .neighbours()
+            .into_iter()
+            .filter(|neighbour| self.contains(*neighbour))
+    }
+
+    /// First point, row by row, whose cell matches
+    pub fn find(&self, mut f: impl FnMut(&T) -> bool) -> Option<Point> {
+        self.iter()
+            .find(|(_, cell)| f(cell))
+            .map(|(point, _)| point)
+    }
+
+    /// The cells of one row, panics if it's outside the grid
+    pub fn row(&self, row: usize) -> &[T] {
+        &self.cells[row * self.cols..(row + 1) * self.cols]
+    }
+
+    /// The cells of one column from top to bottom, panics if it's outside the grid
+    pub fn col(&self, col: usize) -> impl Iterator<Item = &T> + '_ {
+        assert!(col < self.cols, "column is outside the grid");
+        self.cells.iter().skip(col).step_by(self.cols)
+    }
+
+    /// Swaps rows and columns without copying any cells
+    pub fn transpose(&mut self) {
+        // Follow each cycle of the permutation, swapping cells into place
+        let mut placed = vec![false; self.cells.len()];
+        for start in 0..self.cells.len() {
+            let mut idx = start;
+            while !placed[idx] {
+                placed[idx] = true;
+                let next = (idx % self.cols) * self.rows + idx / self.cols;
+                if next == start {
+                    break;
+                }
+                self.cells.swap(start, next);
+                idx = next;
+            }
+        }
+        (self.rows, self.cols) = (self.cols, self.rows);
+    }
+
+    /// Turns the grid upside down, the first row becomes the last
+    pub fn flip_rows(&mut self) {
+        for row in 0..self.rows / 2 {
+            let other = self.rows - 1 - row;
+            for col in 0..self.cols {
+                self.cells
+                    .swap(row * self.cols + col, other * self.cols + col);
+            }
+        }
+    }
+
+    /// Mirrors the grid left to right, the first column becomes the last
+    pub fn flip_cols(&mut self) {
+        if self.cols > 0 {
+            self.cells
+                .chunks_mut(self.cols)
+                .for_each(|row| row.reverse());
+        }
+    }
+
+    /// Rotates a quarter turn clockwise
+    pub fn rotate_90(&mut self) {
+        self.transpose();
+        self.flip_cols();
+    }
+
+    pub fn rotate_180(&mut self) {
+        self.cells.reverse();
+    }
+
+    /// Rotates a quarter turn anticlockwise
+    pub fn rotate_270(&mut self) {
+        self.transpose();
+        self.flip_rows();
+    }
+
+    /// Looks at the grid without changing it, so it can be transposed, rotated or flipped for free
+    pub fn view(&self) -> GridView<'_, T> {
+        GridView {
+            grid: self,
+            transposed: false,
+            flip_rows: false,
+            flip_cols: false,
+        }
+    }
+
+    fn index_of(&self, point: Point) -> Option<usize> {
+        self.contains(point)
+            .then(|| point.row as usize * self.cols + point.col as usize)
+    }
+}
+
+impl<T: PartialEq> Grid<T> {
+    /// Number of cells that differ between two rows, 0 if they're the same
+    pub fn row_diff(&self, a: usize, b: usize) -> usize {
+        diff(self.row(a).iter(), self.row(b).iter())
+    }
+
+    /// Number of cells that differ between two columns, 0 if they're the same
+    pub fn col_diff(&self, a: usize, b: usize) -> usize {
+        diff(self.col(a), self.col(b))
+    }
+}
+
+impl<T: Hash> Grid<T> {
+    /// A cheap stand in for the whole grid, e.g. to spot repeats with the `cycle` module.
+    /// Different grids can very rarely share one, use a clone of the grid when that matters
+    pub fn fingerprint(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        self.hash(&mut hasher);
+        hasher.finish()
+    }
+}
+
+fn diff<'a, T: PartialEq + 'a>(
+    a: impl Iterator<Item = &'a T>,
+    b: impl Iterator<Item = &'a T>,
+) -> usize {
+    a.zip(b).filter(|(a, b)| a != b).count()
+}
+
+/// A transposed, rotated or flipped look at a grid, see `Grid::view`
+pub struct GridView<'a, T> {
+    grid: &'a Grid<T>,
+    // Applied in this order to get from the grid to the view
+    transposed: bool,
+    flip_rows: bool,
+    flip_cols: bool,
+}
+
+impl<T> Clone for GridView<'_, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for GridView<'_, T> {}
+
+impl<'a, T> GridView<'a, T> {
+    pub fn rows(&self) -> usize {
+        if self.transposed {
+            self.grid.cols
+        } else {
+            self.grid.rows
+        }
+    }
+
+    pub fn cols(&self) -> usize {
+        if self.transposed {
+            self.grid.rows
+        } else {
+            self.grid.cols
+        }
+    }
+
+    pub fn contains(&self, point: Point) -> bool {
+        (0..self.rows() as i64).contains(&point.row) && (0..self.cols() as i64).contains(&point.col)
+    }
+
+    pub fn get(&self, point: Point) -> Option<&'a T> {
+        if !self.contains(point) {
+            return None;
+        }
+
+        let mut point = point;
+        if self.flip_rows {
+            point.row = self.rows() as i64 - 1 - point.row;
+        }
+        if self.flip_cols {
+            point.col = self.cols() as i64 - 1 - point.col;
+        }
+        if self.transposed {
+            point = Point::new(point.col, point.row);
+        }
+        self.grid.get(point)
+    }
+
+    /// The cells of one row of the view, panics if it's outside the view
+    pub fn row(&self, row: usize) -> impl Iterator<Item = &'a T> + 'a {
+        assert!(row < self.rows(), "row is outside the view");
+        let view = *self;
+        (0..self.cols()).map(move |col| view.cell(row, col))
+    }
+
+    /// The cells of one column of the view, panics if it's outside the view
+    pub fn col(&self, col: usize) -> impl Iterator<Item = &'a T> + 'a {
+        assert!(col < self.cols(), "column is outside the view");
+        let view = *self;
+        (0..self.rows()).map(move |row| view.cell(row, col))
+    }
+
+    fn cell(&self, row: usize, col: usize) -> &'a T {
+        self.get(Point::new(row as i64, col as i64))
+            .expect("point is outside the view")
+    }
+
+    pub fn transpose(self) -> Self {
+        // Flipping then transposing is transposing then flipping the other way
+        GridView {
+            grid: self.grid,
+            transposed: !self.transposed,
+            flip_rows: self.flip_cols,
+            flip_cols: self.flip_rows,
+        }
+    }
+
+    pub fn flip_rows(self) -> Self {
+        GridView {
+            flip_rows: !self.flip_rows,
+            ..self
+        }
+    }
+
+    pub fn flip_cols(self) -> Self {
+        GridView {
+            flip_cols: !self.flip_cols,
+            ..self
+        }
+    }
+
+    /// A quarter turn clockwise
+    pub fn rotate_90(self) -> Self {
+        self.transpose().flip_cols()
+    }
+
+    pub fn rotate_180(self) -> Self {
+        self.flip_rows().flip_cols()
+    }
+
+    /// A quarter turn anticlockwise
+    pub fn rotate_270(self) -> Self {
+        self.transpose().flip_rows()
+    }
+}
+
+impl<T: PartialEq> GridView<'_, T> {
+    /// Number of cells that differ between two rows of the view
+    pub fn row_diff(&self, a: usize, b: usize) -> usize {
+        diff(self.row(a), self.row(b))
+    }
+
+    /// Number of cells that differ between two columns of the view
+    pub fn col_diff(&self, a: usize, b: usize) -> usize {
+        diff(self.col(a), self.col(b))
+    }
+}
+
+impl<T: Clone> GridView<'_, T> {
+    /// Copies the view into a grid of its own
+    pub fn to_grid(&self) -> Grid<T> {
+        let cells = (0..self.rows())
+            .flat_map(|row| self.row(row).cloned())
+            .collect();
+        Grid::new(self.rows(), self.cols(), cells)
+    }
+}
+
+impl<T> Index<Point> for GridView<'_, T> {
+    type Output = T;
+
+    fn index(&self, point: Point) -> &T {
+        self.get(point).expect("point is outside the view")
+    }
+}
+
+impl FromStr for Grid<char> {
+    type Err = ParseGridErr;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Grid::parse(s, |c| c)
+    }
+}
+
+impl<T> Index<Point> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, point: Point) -> &T {
+        self.get(point).expect("point is outside the grid")
+    }
+}
+
+impl<T> IndexMut<Point> for Grid<T> {
+    fn index_mut(&mut self, point: Point) -> &mut T {
+        self.get_mut(point).expect("point is outside the grid")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GRID: &str = "abc\ndef";
+
+    fn grid(s: &str) -> Grid<char> {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn parse_needs_even_lines() {
+        let parsed = grid(GRID);
+        assert_eq!((parsed.rows(), parsed.cols()), (2, 3));
+        assert_eq!(parsed[Point::new(1, 2)], 'f');
+        assert_eq!(grid("").rows(), 0);
+
+        // Six cells would fit 3 by 2, but the rows don't line up
+        assert_eq!("ab\nc\nabc".parse::<Grid<char>>(), Err(ParseGridErr));
+        assert_eq!("abc\nab".parse::<Grid<char>>(), Err(ParseGridErr));
+    }
+
+    #[test]
+    fn transforms_in_place_match_views() {
+        let original = grid(GRID);
+        type InPlace = fn(&mut Grid<char>);
+        type View = fn(GridView<char>) -> GridView<char>;
+        let cases: [(InPlace, View, &str); 6] = [
+            (Grid::transpose, |v| v.transpose(), "ad\nbe\ncf"),
+            (Grid::flip_rows, |v| v.flip_rows(), "def\nabc"),
+            (Grid::flip_cols, |v| v.flip_cols(), "cba\nfed"),
+            (Grid::rotate_90, |v| v.rotate_90(), "da\neb\nfc"),
+            (Grid::rotate_180, |v| v.rotate_180(), "fed\ncba"),
+            (Grid::rotate_270, |v| v.rotate_270(), "cf\nbe\nad"),
+        ];
+
+        for (in_place, view, expected) in cases {
+            let mut changed = original.clone();
+            in_place(&mut changed);
+            assert_eq!(changed, grid(expected));
+            assert_eq!(view(original.view()).to_grid(), grid(expected));
+        }
+
+        // Transforms of a view stack up
+        let view = original.view().rotate_90().rotate_90().rotate_90();
+        assert_eq!(view.to_grid(), grid("cf\nbe\nad"));
+        assert_eq!(view.rotate_90().to_grid(), original);
+        assert_eq!(view.transpose().flip_rows().to_grid(), grid("fed\ncba"));
+
+        let mut tall = grid("ab\ncd\nef\ngh");
+        tall.transpose();
+        assert_eq!(tall, grid("aceg\nbdfh"));
+    }
+
+    #[test]
+    fn row_and_column_diffs() {
+        // A mirror between the 4th and 5th rows with one smudge
+        let pattern =
+            grid("#...##..#\n#....#..#\n..##..###\n#####.##.\n#####.##.\n..##..###\n#....#..#");
+        assert_eq!(pattern.row_diff(3, 4), 0);
+        assert_eq!(pattern.row_diff(0, 1), 1);
+        assert_eq!(
+            pattern.row(2),
+            &['.', '.', '#', '#', '.', '.', '#', '#', '#']
+        );
+        assert_eq!(pattern.col_diff(0, 1), 3);
+
+        let view = pattern.view().transpose();
+        assert_eq!(view.col_diff(3, 4), 0);
+        assert_eq!(view.row_diff(0, 1), 3);
+        assert_eq!(view.row(0).collect::<String>(), "##.##.#");
+
+        let mut turned = pattern.clone();
+        assert_eq!(turned.fingerprint(), pattern.fingerprint());
+        turned.rotate_180();
+        assert_ne!(turned.fingerprint(), pattern.fingerprint());
+    }
+}
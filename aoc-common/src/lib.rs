@@ -1,3 +1,26 @@
+pub mod bitset;
+pub mod checked;
+pub mod counter;
+pub mod cursor;
+pub mod cycle;
+pub mod disjoint_set;
+pub mod geometry;
+pub mod grid;
+pub mod linear;
+#[cfg(feature = "lines")]
+pub mod lines;
+pub mod math;
+pub mod memo;
+pub mod point;
+pub mod quadratic;
+pub mod rational;
+pub mod report;
+pub mod scanner;
+pub mod search;
+pub mod unicode;
+
+pub use disjoint_set::DisjointSet;
+
 use clap::Parser;
 use std::{fmt::Display, fs, path::PathBuf, time::Instant};
 
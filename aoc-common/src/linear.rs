@@ -0,0 +1,169 @@
+//! Exact solutions of small systems of linear equations, by Gaussian elimination over
+//! `Rational`s so nothing is lost to rounding.
+
+use crate::rational::Rational;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Solution {
+    /// The value of each unknown
+    Unique(Vec<Rational>),
+    /// Not enough independent equations. `particular` is one solution, with every `free`
+    /// unknown set to 0, and any values can be picked for the free ones
+    Many {
+        particular: Vec<Rational>,
+        free: Vec<usize>,
+    },
+    /// The equations contradict each other
+    None,
+}
+
+/// Solves `coefficients * x = constants`, where each row of `coefficients` is one equation.
+/// `None` if the arithmetic overflows. Panics if the rows aren't all the same length or
+/// there isn't one constant per equation
+pub fn solve(coefficients: &[Vec<Rational>], constants: &[Rational]) -> Option<Solution> {
+    assert_eq!(
+        coefficients.len(),
+        constants.len(),
+        "need one constant per equation"
+    );
+    let unknowns = coefficients.first().map_or(0, |row| row.len());
+    assert!(
+        coefficients.iter().all(|row| row.len() == unknowns),
+        "equations have different numbers of unknowns"
+    );
+
+    // Each row has its constant on the end
+    let mut rows: Vec<Vec<Rational>> = coefficients
+        .iter()
+        .zip(constants)
+        .map(|(row, constant)| row.iter().chain([constant]).copied().collect())
+        .collect();
+
+    // Reduced row echelon form, `pivots[row]` is the unknown that row solves for
+    let mut pivots = Vec::new();
+    for col in 0..unknowns {
+        let row = pivots.len();
+        let Some(found) = (row..rows.len()).find(|r| rows[*r][col] != Rational::ZERO) else {
+            // Free unknown
+            continue;
+        };
+        rows.swap(row, found);
+
+        let pivot = rows[row][col];
+        for value in rows[row][col..].iter_mut() {
+            *value = value.checked_div(pivot)?;
+        }
+        let pivot_row = rows[row].clone();
+        for (other, values) in rows.iter_mut().enumerate() {
+            let factor = values[col];
+            if other == row || factor == Rational::ZERO {
+                continue;
+            }
+            for (value, pivot_value) in values[col..].iter_mut().zip(&pivot_row[col..]) {
+                *value = value.checked_sub(factor.checked_mul(*pivot_value)?)?;
+            }
+        }
+        pivots.push(col);
+    }
+
+    // Rows left over have no unknowns, so they must say 0 = 0
+    if rows[pivots.len()..]
+        .iter()
+        .any(|row| row[unknowns] != Rational::ZERO)
+    {
+        return Some(Solution::None);
+    }
+
+    let mut values = vec![Rational::ZERO; unknowns];
+    for (row, col) in pivots.iter().enumerate() {
+        values[*col] = rows[row][unknowns];
+    }
+
+    if pivots.len() == unknowns {
+        Some(Solution::Unique(values))
+    } else {
+        let free = (0..unknowns).filter(|col| !pivots.contains(col)).collect();
+        Some(Solution::Many {
+            particular: values,
+            free,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn system(equations: &[&[i64]]) -> (Vec<Vec<Rational>>, Vec<Rational>) {
+        equations
+            .iter()
+            .map(|equation| {
+                let (constant, coefficients) = equation.split_last().unwrap();
+                (
+                    coefficients.iter().map(|c| Rational::from(*c)).collect(),
+                    Rational::from(*constant),
+                )
+            })
+            .unzip()
+    }
+
+    fn ints(values: &[i64]) -> Vec<Rational> {
+        values.iter().map(|v| Rational::from(*v)).collect()
+    }
+
+    #[test]
+    fn unique_solutions() {
+        // 2x + y - z = 8, -3x - y + 2z = -11, -2x + y + 2z = -3
+        let (a, b) = system(&[&[2, 1, -1, 8], &[-3, -1, 2, -11], &[-2, 1, 2, -3]]);
+        assert_eq!(solve(&a, &b), Some(Solution::Unique(ints(&[2, 3, -1]))));
+
+        // Needs a row swap and has a fractional answer, plus a redundant equation
+        let (a, b) = system(&[&[0, 2, 1], &[3, 0, 1], &[3, 2, 2]]);
+        assert_eq!(
+            solve(&a, &b),
+            Some(Solution::Unique(vec![
+                Rational::new(1, 3),
+                Rational::new(1, 2)
+            ]))
+        );
+    }
+
+    #[test]
+    fn singular_and_underdetermined() {
+        // Parallel lines never meet
+        let (a, b) = system(&[&[1, 1, 1], &[2, 2, 3]]);
+        assert_eq!(solve(&a, &b), Some(Solution::None));
+
+        // The same line twice
+        let (a, b) = system(&[&[1, 1, 2], &[2, 2, 4]]);
+        assert_eq!(
+            solve(&a, &b),
+            Some(Solution::Many {
+                particular: ints(&[2, 0]),
+                free: vec![1],
+            })
+        );
+
+        // x + y + z = 6, x - y = 0 leaves z free
+        let (a, b) = system(&[&[1, 1, 1, 6], &[1, -1, 0, 0]]);
+        assert_eq!(
+            solve(&a, &b),
+            Some(Solution::Many {
+                particular: ints(&[3, 3, 0]),
+                free: vec![2],
+            })
+        );
+
+        assert_eq!(
+            solve(&[vec![Rational::ZERO]], &[Rational::ONE]),
+            Some(Solution::None)
+        );
+    }
+
+    #[test]
+    fn overflow_is_none() {
+        let big = Rational::from(i128::MAX);
+        let a = vec![vec![big, Rational::ONE], vec![Rational::ONE, big]];
+        assert_eq!(solve(&a, &[Rational::ONE, Rational::ONE]), None);
+    }
+}
@@ -0,0 +1,228 @@
+//! Folds over every line of a document too big to read into memory. The file is memory
+//! mapped, split into chunks on line boundaries and the chunks are folded in parallel, so
+//! memory use doesn't grow with the size of the file.
+
+use crate::{
+    checked::{CheckedIterator, Overflow},
+    report::Report,
+};
+use memmap2::Mmap;
+use rayon::prelude::*;
+use std::{fs::File, io, path::Path};
+
+/// Chunks are at least this many bytes, apart from the last one
+pub const CHUNK_SIZE: usize = 4 << 20;
+
+/// A memory mapped file
+pub struct Document {
+    /// `None` for an empty file, which can't be mapped
+    map: Option<Mmap>,
+}
+
+impl Document {
+    pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        let file = File::open(path)?;
+        if file.metadata()?.len() == 0 {
+            return Ok(Document { map: None });
+        }
+        // SAFETY: the map is only read, the file changing underneath us would give wrong
+        // answers but the bytes are never treated as anything other than bytes
+        let map = unsafe { Mmap::map(&file)? };
+        Ok(Document { map: Some(map) })
+    }
+
+    pub fn bytes(&self) -> &[u8] {
+        self.map.as_deref().unwrap_or_default()
+    }
+}
+
+/// Part of a document made of whole lines
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Chunk<'a> {
+    /// Number of the first line in the chunk, from 1
+    pub first_line: usize,
+    pub bytes: &'a [u8],
+}
+
+impl<'a> Chunk<'a> {
+    /// The lines with their numbers. Like `BufRead::lines`, the `\n` or `\r\n` on the end
+    /// is dropped and there's no empty line after a final newline
+    pub fn lines(&self) -> impl Iterator<Item = (usize, &'a [u8])> {
+        let bytes = self.bytes.strip_suffix(b"\n").unwrap_or(self.bytes);
+        let lines = (!self.bytes.is_empty()).then(|| bytes.split(|b| *b == b'\n'));
+        lines
+            .into_iter()
+            .flatten()
+            .map(|line| line.strip_suffix(b"\r").unwrap_or(line))
+            .zip(self.first_line..)
+            .map(|(line, number)| (number, line))
+    }
+}
+
+/// Splits `bytes` into chunks of at least `size` bytes, each ending just after a newline
+/// (or at the end)
+pub fn chunks(bytes: &[u8], size: usize) -> Vec<Chunk<'_>> {
+    assert!(size > 0, "chunks can't be empty");
+    let mut ends = Vec::new();
+    let mut start = 0;
+    while start < bytes.len() {
+        let from = (start + size).min(bytes.len()) - 1;
+        let end = match bytes[from..].iter().position(|b| *b == b'\n') {
+            Some(newline) => from + newline + 1,
+            None => bytes.len(),
+        };
+        ends.push((start, end));
+        start = end;
+    }
+
+    // Counting newlines is much quicker than folding, so do it first to number the lines
+    let newlines: Vec<usize> = ends
+        .par_iter()
+        .map(|(start, end)| bytes[*start..*end].iter().filter(|b| **b == b'\n').count())
+        .collect();
+    let mut first_line = 1;
+    ends.iter()
+        .zip(newlines)
+        .map(|((start, end), newlines)| {
+            let chunk = Chunk {
+                first_line,
+                bytes: &bytes[*start..*end],
+            };
+            first_line += newlines;
+            chunk
+        })
+        .collect()
+}
+
+/// Folds each chunk's lines, in parallel, into an `A` started from `init` then merges them
+/// in order. `fold` gets each line's number, counted from 1
+pub fn fold_lines<A, I, F, M>(bytes: &[u8], init: I, fold: F, merge: M) -> A
+where
+    A: Send,
+    I: Fn() -> A + Sync + Send,
+    F: Fn(&mut A, usize, &[u8]) + Sync + Send,
+    M: Fn(A, A) -> A + Sync + Send,
+{
+    fold_chunks(chunks(bytes, CHUNK_SIZE), init, fold, merge)
+}
+
+/// `fold_lines` over chunks that have already been split
+pub fn fold_chunks<A, I, F, M>(chunks: Vec<Chunk>, init: I, fold: F, merge: M) -> A
+where
+    A: Send,
+    I: Fn() -> A + Sync + Send,
+    F: Fn(&mut A, usize, &[u8]) + Sync + Send,
+    M: Fn(A, A) -> A + Sync + Send,
+{
+    chunks
+        .into_par_iter()
+        .map(|chunk| {
+            let mut acc = init();
+            for (number, line) in chunk.lines() {
+                fold(&mut acc, number, line);
+            }
+            acc
+        })
+        .reduce(&init, merge)
+}
+
+/// Adds up a value read from each line. `read` gets the line's number and can note anything
+/// wrong with it in the report, lines that aren't UTF-8 are skipped before it sees them. Once
+/// the sum overflows the rest of that chunk isn't read, so the report may be missing lines
+pub fn sum_lines<F>(bytes: &[u8], read: F) -> (Result<u64, Overflow>, Report)
+where
+    F: Fn(usize, &str, &mut Report) -> Option<u64> + Sync + Send,
+{
+    chunks(bytes, CHUNK_SIZE)
+        .into_par_iter()
+        .map(|chunk| {
+            let mut report = Report::new();
+            let sum = chunk
+                .lines()
+                .filter_map(|(number, line)| match std::str::from_utf8(line) {
+                    Ok(line) => read(number, line, &mut report),
+                    Err(_) => {
+                        report.skip(number, "not valid UTF-8");
+                        None
+                    }
+                })
+                .checked_sum();
+            (sum, report)
+        })
+        .reduce(
+            || (Ok(0), Report::new()),
+            |(a, mut report), (b, later)| {
+                report.append(later);
+                let sum = a.and_then(|a| b.and_then(|b| [a, b].into_iter().checked_sum()));
+                (sum, report)
+            },
+        )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn numbered(bytes: &[u8], size: usize) -> Vec<(usize, &[u8])> {
+        chunks(bytes, size).iter().flat_map(|c| c.lines()).collect()
+    }
+
+    #[test]
+    fn chunks_split_on_lines() {
+        let text = b"one\ntwo\r\n\nfour\nfive";
+        let expected: Vec<(usize, &[u8])> = vec![
+            (1, b"one"),
+            (2, b"two"),
+            (3, b""),
+            (4, b"four"),
+            (5, b"five"),
+        ];
+        for size in 1..=text.len() + 1 {
+            let found = chunks(text, size);
+            assert!(found.iter().all(|c| !c.bytes.is_empty()), "{size}");
+            assert_eq!(numbered(text, size), expected, "{size}");
+        }
+
+        assert_eq!(numbered(b"a\nb\n", 1), vec![(1, &b"a"[..]), (2, b"b")]);
+        assert_eq!(chunks(b"", 4), vec![]);
+    }
+
+    #[test]
+    fn fold_in_order() {
+        let text: String = (1..=10_000).map(|n| format!("{n}\n")).collect();
+        let found = fold_chunks(
+            chunks(text.as_bytes(), 100),
+            Vec::new,
+            |found, number, line| {
+                let n: usize = std::str::from_utf8(line).unwrap().parse().unwrap();
+                assert_eq!(n, number);
+                found.push(n);
+            },
+            |mut a, b| {
+                a.extend(b);
+                a
+            },
+        );
+        assert_eq!(found, (1..=10_000).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn sums_and_reports() {
+        let read = |number: usize, line: &str, report: &mut Report| {
+            line.parse()
+                .map_err(|_| report.skip(number, "not a number"))
+                .ok()
+        };
+        let (sum, report) = sum_lines(b"1\nx\n\xff\n3\n", read);
+        assert_eq!(sum, Ok(4));
+        assert_eq!(
+            report.to_string(),
+            "line 2: skipped, not a number\n\
+             line 3: skipped, not valid UTF-8\n\
+             2 skipped, 0 ambiguous\n"
+        );
+
+        let (sum, _) = sum_lines(b"18446744073709551615\n1", read);
+        assert_eq!(sum, Err(Overflow::Sum));
+    }
+}
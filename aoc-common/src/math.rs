@@ -0,0 +1,287 @@
+use num_traits::{CheckedMul, One, PrimInt, Signed};
+
+/// Greatest common divisor, never negative. `gcd(0, 0)` is 0
+///
+/// Panics if the gcd doesn't fit, which only happens for `gcd(T::MIN, 0)` and
+/// `gcd(T::MIN, T::MIN)` with signed `T`. `checked_gcd` gives `None` instead.
+pub fn gcd<T: PrimInt>(a: T, b: T) -> T {
+    checked_gcd(a, b).expect("gcd is too big for the type")
+}
+
+/// Greatest common divisor, never negative, `None` if it doesn't fit
+pub fn checked_gcd<T: PrimInt>(a: T, b: T) -> Option<T> {
+    let (mut a, mut b) = (a, b);
+    while b != T::zero() {
+        (a, b) = (b, a % b);
+    }
+
+    if a < T::zero() {
+        T::zero().checked_sub(&a)
+    } else {
+        Some(a)
+    }
+}
+
+/// Least common multiple, `None` if it overflows
+pub fn lcm<T: PrimInt>(a: T, b: T) -> Option<T> {
+    if a == T::zero() || b == T::zero() {
+        return Some(T::zero());
+    }
+
+    let lcm = (a / checked_gcd(a, b)?).checked_mul(&b)?;
+    if lcm < T::zero() {
+        T::zero().checked_sub(&lcm)
+    } else {
+        Some(lcm)
+    }
+}
+
+/// Greatest common divisor of all the values, 0 if there are none. Panics like `gcd`
+pub fn gcd_all<T: PrimInt>(values: impl IntoIterator<Item = T>) -> T {
+    values.into_iter().fold(T::zero(), gcd)
+}
+
+/// Least common multiple of all the values (e.g. cycle lengths), `None` if it overflows
+pub fn lcm_all<T: PrimInt>(values: impl IntoIterator<Item = T>) -> Option<T> {
+    values
+        .into_iter()
+        .try_fold(T::one(), |acc, value| lcm(acc, value))
+}
+
+/// Product of all the values, `None` if it overflows. `CheckedIterator::checked_product` is
+/// the same as an iterator adapter
+pub fn checked_product<T: CheckedMul + One>(values: impl IntoIterator<Item = T>) -> Option<T> {
+    values
+        .into_iter()
+        .try_fold(T::one(), |acc, value| acc.checked_mul(&value))
+}
+
+/// `a` modulo `m`, always between 0 and `m` even when `a` is negative
+pub fn modulo<T: PrimInt>(a: T, m: T) -> T {
+    let r = a % m;
+    if r < T::zero() {
+        r + m
+    } else {
+        r
+    }
+}
+
+/// `a * b` modulo `m` without overflowing, `m` must be positive
+pub fn mul_mod<T: PrimInt>(a: T, b: T, m: T) -> T {
+    let (mut a, mut b) = (modulo(a, m), modulo(b, m));
+    if let Some(product) = a.checked_mul(&b) {
+        return product % m;
+    }
+
+    // Double and add so that nothing gets bigger than m
+    let add_mod = |x: T, y: T| if x >= m - y { x - (m - y) } else { x + y };
+    let mut result = T::zero();
+    while b > T::zero() {
+        if b & T::one() == T::one() {
+            result = add_mod(result, a);
+        }
+        a = add_mod(a, a);
+        b = b >> 1;
+    }
+    result
+}
+
+/// `base` to the power of `exp` modulo `m`, `m` must be positive
+pub fn mod_pow<T: PrimInt>(base: T, exp: u64, m: T) -> T {
+    let mut base = modulo(base, m);
+    let mut exp = exp;
+    let mut result = T::one() % m;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = mul_mod(result, base, m);
+        }
+        base = mul_mod(base, base, m);
+        exp >>= 1;
+    }
+    result
+}
+
+/// Returns `(g, x, y)` where `a * x + b * y == g` and `g` is the gcd of `a` and `b`
+pub fn extended_gcd<T: PrimInt + Signed>(a: T, b: T) -> (T, T, T) {
+    let (mut old_r, mut r) = (a, b);
+    let (mut old_x, mut x) = (T::one(), T::zero());
+    let (mut old_y, mut y) = (T::zero(), T::one());
+
+    while r != T::zero() {
+        let q = old_r / r;
+        (old_r, r) = (r, old_r - q * r);
+        (old_x, x) = (x, old_x - q * x);
+        (old_y, y) = (y, old_y - q * y);
+    }
+
+    if old_r < T::zero() {
+        (-old_r, -old_x, -old_y)
+    } else {
+        (old_r, old_x, old_y)
+    }
+}
+
+/// The `x` where `a * x` is 1 modulo `m`, `None` if `a` and `m` are not coprime
+pub fn mod_inverse<T: PrimInt + Signed>(a: T, m: T) -> Option<T> {
+    let (g, x, _) = extended_gcd(modulo(a, m), m);
+    (g == T::one()).then(|| modulo(x, m))
+}
+
+/// Chinese remainder theorem, finds the `x` where `x` is `residue` modulo `modulus` for every
+/// pair. The moduli do not have to be coprime.
+///
+/// Returns `(x, lcm of the moduli)`, or `None` if the congruences disagree or the lcm overflows.
+pub fn crt<T: PrimInt + Signed>(congruences: impl IntoIterator<Item = (T, T)>) -> Option<(T, T)> {
+    congruences
+        .into_iter()
+        .try_fold((T::zero(), T::one()), |(x, m), (residue, modulus)| {
+            let g = gcd(m, modulus);
+            let difference = modulo(residue, modulus) - modulo(x, modulus);
+            if difference % g != T::zero() {
+                return None;
+            }
+
+            // Solve x + m * k == residue (mod modulus) for k
+            let reduced = modulus / g;
+            let k = mul_mod(difference / g, mod_inverse(m / g, reduced)?, reduced);
+            let combined = m.checked_mul(&reduced)?;
+            // k is below reduced so this stays below combined
+            Some((x + m * k, combined))
+        })
+}
+
+/// Integer square root, the largest `r` where `r * r <= n`
+///
+/// Panics if `n` is negative.
+pub fn isqrt<T: PrimInt>(n: T) -> T {
+    integer_root(n, 2)
+}
+
+/// Integer cube root, the largest `r` where `r * r * r <= n`
+///
+/// Panics if `n` is negative.
+pub fn icbrt<T: PrimInt>(n: T) -> T {
+    integer_root(n, 3)
+}
+
+fn integer_root<T: PrimInt>(n: T, degree: usize) -> T {
+    assert!(n >= T::zero(), "root of a negative number");
+    let power = |r: T| checked_product(std::iter::repeat_n(r, degree));
+
+    // Start from the float estimate and correct it, it can be off by a little for big numbers
+    let estimate = n.to_f64().unwrap().powf(1.0 / degree as f64);
+    let mut root = T::from(estimate).unwrap_or_else(T::max_value);
+    while power(root).is_none_or(|p| p > n) {
+        root = root - T::one();
+    }
+    while power(root + T::one()).is_some_and(|p| p <= n) {
+        root = root + T::one();
+    }
+    root
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    #[test]
+    fn roots_at_type_limits() {
+        assert_eq!(isqrt(0_u8), 0);
+        assert_eq!(isqrt(u8::MAX), 15);
+        assert_eq!(isqrt(i64::MAX), 3037000499);
+        assert_eq!(isqrt(u128::MAX), u64::MAX as u128);
+        assert_eq!(icbrt(u64::MAX), 2642245);
+        assert_eq!(icbrt(27_i32), 3);
+        assert_eq!(icbrt(26_i32), 2);
+    }
+
+    #[test]
+    fn gcd_of_signed_min() {
+        assert_eq!(gcd(i64::MIN, 6), 2);
+        assert_eq!(checked_gcd(i64::MIN, 0), None);
+        assert_eq!(checked_gcd(i64::MIN, i64::MIN), None);
+        assert_eq!(checked_gcd(i64::MIN + 1, 0), Some(i64::MAX));
+        assert_eq!(lcm(i64::MIN, i64::MIN), None);
+        assert_eq!(lcm(i64::MIN, 0), Some(0));
+    }
+
+    #[test]
+    #[should_panic(expected = "gcd is too big")]
+    fn gcd_of_signed_min_panics() {
+        gcd(i64::MIN, 0);
+    }
+
+    #[test]
+    fn crt_examples() {
+        assert_eq!(crt([(2_i64, 3), (3, 5), (2, 7)]), Some((23, 105)));
+        // Moduli sharing a factor
+        assert_eq!(crt([(3_i64, 4), (5, 6)]), Some((11, 12)));
+        assert_eq!(crt([(1_i64, 4), (2, 6)]), None);
+        assert_eq!(crt([(0_i64, i64::MAX), (0, i64::MAX - 1)]), None);
+    }
+
+    proptest! {
+        #[test]
+        fn gcd_lcm_relation(a in 0_u64..1 << 32, b in 0_u64..1 << 32) {
+            let g = gcd(a, b);
+            if g != 0 {
+                prop_assert_eq!(a % g, 0);
+                prop_assert_eq!(b % g, 0);
+            }
+            prop_assert_eq!(g * lcm(a, b).unwrap(), a * b);
+            prop_assert_eq!(gcd(-(a as i64), b as i64), g as i64);
+        }
+
+        #[test]
+        fn isqrt_bounds(n: u64) {
+            let r = isqrt(n) as u128;
+            prop_assert!(r * r <= n as u128 && (r + 1) * (r + 1) > n as u128);
+        }
+
+        #[test]
+        fn icbrt_bounds(n: u64) {
+            let r = icbrt(n) as u128;
+            prop_assert!(r * r * r <= n as u128 && (r + 1) * (r + 1) * (r + 1) > n as u128);
+        }
+
+        #[test]
+        fn mul_mod_matches_wide(a: u64, b: u64, m in 1_u64..) {
+            let expected = (a as u128 * b as u128 % m as u128) as u64;
+            prop_assert_eq!(mul_mod(a, b, m), expected);
+        }
+
+        #[test]
+        fn mod_pow_matches_repeated_mul(base: i64, exp in 0_u64..200, m in 1_i64..) {
+            let expected = (0..exp).fold(1 % m, |acc, _| mul_mod(acc, base, m));
+            prop_assert_eq!(mod_pow(base, exp, m), expected);
+        }
+
+        #[test]
+        fn mod_inverse_is_inverse(a: i64, m in 2_i64..) {
+            match mod_inverse(a, m) {
+                Some(inverse) => prop_assert_eq!(mul_mod(a, inverse, m), 1),
+                None => prop_assert_ne!(gcd(a, m), 1),
+            }
+        }
+
+        #[test]
+        fn crt_satisfies_congruences(
+            congruences in prop::collection::vec((any::<i64>(), 1_i64..10_000), 1..5)
+        ) {
+            if let Some((x, m)) = crt(congruences.iter().copied()) {
+                prop_assert!((0..m).contains(&x));
+                for (residue, modulus) in congruences {
+                    prop_assert_eq!(modulo(x, modulus), modulo(residue, modulus));
+                }
+            }
+        }
+
+        #[test]
+        fn checked_product_detects_overflow(values in prop::collection::vec(any::<u32>(), 0..4)) {
+            let wide = values.iter().try_fold(1_u64, |acc, v| acc.checked_mul(*v as u64));
+            let expected = wide.and_then(|p| u32::try_from(p).ok());
+            prop_assert_eq!(checked_product(values), expected);
+        }
+    }
+}
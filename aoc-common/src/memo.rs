@@ -0,0 +1,106 @@
+use std::{collections::HashMap, hash::Hash};
+
+/// Caches a recursive function so each key is only computed once.
+///
+/// The function is given a way to recurse as its first argument, calls through it are cached too:
+///
+/// ```
+/// use aoc_common::memo::Memo;
+///
+/// let mut fib = Memo::new(|fib: &mut dyn FnMut(u64) -> u64, n: u64| {
+///     if n < 2 { n } else { fib(n - 1) + fib(n - 2) }
+/// });
+/// assert_eq!(fib.get(90), 2880067194370816120);
+/// ```
+pub struct Memo<K, V, F> {
+    cache: HashMap<K, V>,
+    f: F,
+}
+
+impl<K, V, F> Memo<K, V, F>
+where
+    K: Hash + Eq + Clone,
+    V: Clone,
+    F: Fn(&mut dyn FnMut(K) -> V, K) -> V,
+{
+    pub fn new(f: F) -> Self {
+        Memo {
+            cache: HashMap::new(),
+            f,
+        }
+    }
+
+    pub fn get(&mut self, key: K) -> V {
+        cached(&self.f, &mut self.cache, key)
+    }
+
+    /// Number of keys computed so far
+    pub fn len(&self) -> usize {
+        self.cache.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cache.is_empty()
+    }
+}
+
+fn cached<K, V, F>(f: &F, cache: &mut HashMap<K, V>, key: K) -> V
+where
+    K: Hash + Eq + Clone,
+    V: Clone,
+    F: Fn(&mut dyn FnMut(K) -> V, K) -> V,
+{
+    if let Some(value) = cache.get(&key) {
+        return value.clone();
+    }
+
+    let value = f(&mut |next| cached(f, cache, next), key.clone());
+    cache.insert(key, value.clone());
+    value
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Counts the ways to fill in the `?` springs so the damaged (`#`) groups match `groups`
+    fn arrangements(springs: &str, groups: &[usize]) -> u64 {
+        let springs = springs.as_bytes();
+        let mut count = Memo::new(
+            |count: &mut dyn FnMut((usize, usize)) -> u64, (spring, group): (usize, usize)| {
+                let Some(&next) = springs.get(spring) else {
+                    return (group == groups.len()) as u64;
+                };
+
+                let mut total = 0;
+                if next != b'#' {
+                    // Operational spring
+                    total += count((spring + 1, group));
+                }
+                if next != b'.' && group < groups.len() {
+                    // Start of a damaged group, it has to fit and be followed by an operational one
+                    let end = spring + groups[group];
+                    let fits = end <= springs.len()
+                        && springs[spring..end].iter().all(|s| *s != b'.')
+                        && springs.get(end) != Some(&b'#');
+                    if fits {
+                        total += count(((end + 1).min(springs.len()), group + 1));
+                    }
+                }
+                total
+            },
+        );
+        count.get((0, 0))
+    }
+
+    #[test]
+    fn spring_arrangements() {
+        assert_eq!(arrangements("???.###", &[1, 1, 3]), 1);
+        assert_eq!(arrangements(".??..??...?##.", &[1, 1, 3]), 4);
+        assert_eq!(arrangements("?###????????", &[3, 2, 1]), 10);
+
+        // Unfolded five times, far too many branches without the cache
+        let unfolded = ["?###????????"; 5].join("?");
+        assert_eq!(arrangements(&unfolded, &[3, 2, 1].repeat(5)), 506250);
+    }
+}
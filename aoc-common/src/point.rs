@@ -0,0 +1,123 @@
+use std::ops::{Add, Mul, Neg, Sub};
+
+/// A location on a grid, rows grow downwards and columns grow to the right
+#[derive(Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord, Clone, Copy)]
+pub struct Point {
+    pub row: i64,
+    pub col: i64,
+}
+
+impl Point {
+    pub const UP: Point = Point::new(-1, 0);
+    pub const DOWN: Point = Point::new(1, 0);
+    pub const LEFT: Point = Point::new(0, -1);
+    pub const RIGHT: Point = Point::new(0, 1);
+
+    pub const fn new(row: i64, col: i64) -> Self {
+        Point { row, col }
+    }
+
+    /// The four points sharing an edge with this one
+    pub fn neighbours(self) -> [Point; 4] {
+        [Point::UP, Point::RIGHT, Point::DOWN, Point::LEFT].map(|step| self + step)
+    }
+
+    /// The eight points sharing an edge or corner with this one
+    pub fn neighbours_diagonal(self) -> [Point; 8] {
+        [
+            Point::UP + Point::LEFT,
+            Point::UP,
+            Point::UP + Point::RIGHT,
+            Point::RIGHT,
+            Point::DOWN + Point::RIGHT,
+            Point::DOWN,
+            Point::DOWN + Point::LEFT,
+            Point::LEFT,
+        ]
+        .map(|step| self + step)
+    }
+
+    /// Number of orthogonal steps between the two points
+    pub fn manhattan(self, other: Point) -> usize {
+        (self.row.abs_diff(other.row) + self.col.abs_diff(other.col)) as usize
+    }
+}
+
+impl Add<Point> for Point {
+    type Output = Self;
+
+    fn add(self, other: Point) -> Self {
+        Point::new(self.row + other.row, self.col + other.col)
+    }
+}
+
+impl Sub<Point> for Point {
+    type Output = Self;
+
+    fn sub(self, other: Point) -> Self {
+        Point::new(self.row - other.row, self.col - other.col)
+    }
+}
+
+impl Mul<i64> for Point {
+    type Output = Self;
+
+    fn mul(self, scale: i64) -> Self {
+        Point::new(self.row * scale, self.col * scale)
+    }
+}
+
+impl Neg for Point {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Point::new(-self.row, -self.col)
+    }
+}
+
+impl<T> From<(T, T)> for Point
+where
+    T: Into<i64>,
+{
+    fn from(value: (T, T)) -> Self {
+        Point::new(value.0.into(), value.1.into())
+    }
+}
+
+/// A location in 3D space
+#[derive(Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord, Clone, Copy)]
+pub struct Point3 {
+    pub x: i64,
+    pub y: i64,
+    pub z: i64,
+}
+
+impl Point3 {
+    pub const fn new(x: i64, y: i64, z: i64) -> Self {
+        Point3 { x, y, z }
+    }
+}
+
+impl Add<Point3> for Point3 {
+    type Output = Self;
+
+    fn add(self, other: Point3) -> Self {
+        Point3::new(self.x + other.x, self.y + other.y, self.z + other.z)
+    }
+}
+
+impl Sub<Point3> for Point3 {
+    type Output = Self;
+
+    fn sub(self, other: Point3) -> Self {
+        Point3::new(self.x - other.x, self.y - other.y, self.z - other.z)
+    }
+}
+
+impl Mul<i64> for Point3 {
+    type Output = Self;
+
+    fn mul(self, scale: i64) -> Self {
+        Point3::new(self.x * scale, self.y * scale, self.z * scale)
+    }
+}
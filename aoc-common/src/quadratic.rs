@@ -0,0 +1,80 @@
+use crate::math::isqrt;
+use std::ops::RangeInclusive;
+
+/// Returns the integers `x` where `x * x - b * x + c < 0`, which are the integers strictly
+/// between the two roots of the quadratic. This is the same as `x * (b - x) > c`.
+///
+/// Returns `None` if there are no such integers, including when the best `x` only ties `c`.
+pub fn below_zero(b: u128, c: u128) -> Option<RangeInclusive<u128>> {
+    // The quadratic is symmetric around b / 2, split b into a middle and a remainder
+    let middle = b / 2;
+    let remainder = b % 2;
+    let Some(peak) = middle.checked_mul(middle + remainder) else {
+        return Some(below_zero_past_u128(b, c));
+    };
+
+    // How far x can move away from the middle is the largest k where k * (k + remainder) < excess
+    let excess = peak.checked_sub(c).filter(|excess| *excess > 0)?;
+    let mut k = isqrt(excess - 1);
+    if k.checked_mul(k + remainder).is_none_or(|v| v > excess - 1) {
+        k -= 1;
+    }
+
+    Some((middle - k)..=(middle + remainder + k))
+}
+
+/// `below_zero` for when `b` is `2^65` or more. The peak is then too big for a `u128`, so it's
+/// bigger than any `c`, and the lower end is found by bisecting instead
+fn below_zero_past_u128(b: u128, c: u128) -> RangeInclusive<u128> {
+    // A product too big to fit is bigger than c
+    let wins = |x: u128| x.checked_mul(b - x).is_none_or(|product| product > c);
+    let (mut low, mut high) = (0, b / 2);
+    while low < high {
+        let mid = low + (high - low) / 2;
+        if wins(mid) {
+            high = mid;
+        } else {
+            low = mid + 1;
+        }
+    }
+    low..=(b - low)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn below_zero_matches_brute_force() {
+        for b in 0..40 {
+            for c in 0..(b * b / 4 + 2) {
+                let expected: Vec<u128> = (0..=b).filter(|x| x * (b - x) > c).collect();
+                let found: Vec<u128> = below_zero(b, c).into_iter().flatten().collect();
+                assert_eq!(found, expected, "b = {b}, c = {c}");
+            }
+        }
+    }
+
+    #[test]
+    fn below_zero_large() {
+        // Ties with the record at 10^19 and 2 * 10^19 so neither end wins
+        let b = 3 * 10_u128.pow(19);
+        let c = 2 * 10_u128.pow(38);
+        assert_eq!(
+            below_zero(b, c),
+            Some((10_u128.pow(19) + 1)..=(2 * 10_u128.pow(19) - 1))
+        );
+    }
+
+    #[test]
+    fn below_zero_peak_past_u128() {
+        assert_eq!(below_zero(u128::MAX, 0), Some(1..=u128::MAX - 1));
+        // 1 * (b - 1) doesn't beat u128::MAX, but 2 * (b - 2) is too big to fit
+        assert_eq!(below_zero(u128::MAX, u128::MAX), Some(2..=u128::MAX - 2));
+
+        // Ties at x = 2^63 (so does b - x), one more wins
+        let b = 1_u128 << 65;
+        let c = (1_u128 << 63) * (b - (1 << 63));
+        assert_eq!(below_zero(b, c), Some((1 << 63) + 1..=b - (1 << 63) - 1));
+    }
+}
@@ -0,0 +1,234 @@
+use crate::math::gcd;
+use std::{
+    cmp::Ordering,
+    fmt,
+    ops::{Add, Div, Mul, Neg, Sub},
+};
+
+/// An exact fraction, always kept in lowest terms with a positive denominator.
+/// The operators panic on overflow or division by zero, the `checked_` methods don't
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Rational {
+    numer: i128,
+    denom: i128,
+}
+
+impl Rational {
+    pub const ZERO: Rational = Rational { numer: 0, denom: 1 };
+    pub const ONE: Rational = Rational { numer: 1, denom: 1 };
+
+    /// Panics if `denom` is 0
+    pub fn new(numer: i128, denom: i128) -> Self {
+        Self::checked_new(numer, denom).expect("rational has a zero denominator or overflowed")
+    }
+
+    /// `None` if `denom` is 0 or the fraction can't be normalised
+    pub fn checked_new(numer: i128, denom: i128) -> Option<Self> {
+        if denom == 0 || numer == i128::MIN || denom == i128::MIN {
+            // i128::MIN can't be negated to normalise the sign
+            return None;
+        }
+
+        let divisor = gcd(numer, denom);
+        let (numer, denom) = (numer / divisor, denom / divisor);
+        if denom < 0 {
+            Some(Rational {
+                numer: numer.checked_neg()?,
+                denom: denom.checked_neg()?,
+            })
+        } else {
+            Some(Rational { numer, denom })
+        }
+    }
+
+    pub fn numer(&self) -> i128 {
+        self.numer
+    }
+
+    pub fn denom(&self) -> i128 {
+        self.denom
+    }
+
+    pub fn is_integer(&self) -> bool {
+        self.denom == 1
+    }
+
+    /// The value if it's a whole number
+    pub fn to_integer(&self) -> Option<i128> {
+        self.is_integer().then_some(self.numer)
+    }
+
+    pub fn checked_add(self, other: Self) -> Option<Self> {
+        // Scale by the lcm of the denominators rather than their product to stay small
+        let divisor = gcd(self.denom, other.denom);
+        let numer = self
+            .numer
+            .checked_mul(other.denom / divisor)?
+            .checked_add(other.numer.checked_mul(self.denom / divisor)?)?;
+        Self::checked_new(numer, self.denom.checked_mul(other.denom / divisor)?)
+    }
+
+    pub fn checked_sub(self, other: Self) -> Option<Self> {
+        self.checked_add(other.checked_neg()?)
+    }
+
+    pub fn checked_mul(self, other: Self) -> Option<Self> {
+        // Cancel across first so the products can't overflow needlessly
+        let a = gcd(self.numer, other.denom);
+        let b = gcd(other.numer, self.denom);
+        Self::checked_new(
+            (self.numer / a).checked_mul(other.numer / b)?,
+            (self.denom / b).checked_mul(other.denom / a)?,
+        )
+    }
+
+    /// `None` if `other` is 0 or the result overflows
+    pub fn checked_div(self, other: Self) -> Option<Self> {
+        self.checked_mul(other.checked_recip()?)
+    }
+
+    pub fn checked_neg(self) -> Option<Self> {
+        Self::checked_new(self.numer.checked_neg()?, self.denom)
+    }
+
+    /// `None` for 0
+    pub fn checked_recip(self) -> Option<Self> {
+        Self::checked_new(self.denom, self.numer)
+    }
+
+    pub fn abs(self) -> Self {
+        if self.numer < 0 {
+            -self
+        } else {
+            self
+        }
+    }
+}
+
+impl Default for Rational {
+    fn default() -> Self {
+        Rational::ZERO
+    }
+}
+
+impl From<i64> for Rational {
+    fn from(value: i64) -> Self {
+        Rational::from(value as i128)
+    }
+}
+
+impl From<i128> for Rational {
+    fn from(value: i128) -> Self {
+        Rational {
+            numer: value,
+            denom: 1,
+        }
+    }
+}
+
+impl Ord for Rational {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // Denominators are positive so cross multiplying keeps the order
+        let left = self.numer.checked_mul(other.denom);
+        let right = other.numer.checked_mul(self.denom);
+        match (left, right) {
+            (Some(left), Some(right)) => left.cmp(&right),
+            _ => self
+                .checked_sub(*other)
+                .expect("rational overflowed")
+                .numer
+                .cmp(&0),
+        }
+    }
+}
+
+impl PartialOrd for Rational {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl fmt::Display for Rational {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_integer() {
+            write!(f, "{}", self.numer)
+        } else {
+            write!(f, "{}/{}", self.numer, self.denom)
+        }
+    }
+}
+
+impl Add for Rational {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        self.checked_add(other).expect("rational overflowed")
+    }
+}
+
+impl Sub for Rational {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        self.checked_sub(other).expect("rational overflowed")
+    }
+}
+
+impl Mul for Rational {
+    type Output = Self;
+
+    fn mul(self, other: Self) -> Self {
+        self.checked_mul(other).expect("rational overflowed")
+    }
+}
+
+impl Div for Rational {
+    type Output = Self;
+
+    fn div(self, other: Self) -> Self {
+        self.checked_div(other)
+            .expect("rational divided by zero or overflowed")
+    }
+}
+
+impl Neg for Rational {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        self.checked_neg().expect("rational overflowed")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn arithmetic_stays_in_lowest_terms() {
+        let half = Rational::new(2, 4);
+        let third = Rational::new(-1, -3);
+        assert_eq!((half.numer(), half.denom()), (1, 2));
+        assert_eq!(half + third, Rational::new(5, 6));
+        assert_eq!(third - half, Rational::new(-1, 6));
+        assert_eq!(half * third, Rational::new(1, 6));
+        assert_eq!(half / third, Rational::new(3, 2));
+        assert_eq!((half / half).to_integer(), Some(1));
+        assert!(third < half && -half < third);
+        assert_eq!(Rational::new(3, -6).to_string(), "-1/2");
+    }
+
+    #[test]
+    fn overflow_is_caught() {
+        let big = Rational::from(i128::MAX);
+        assert_eq!(big.checked_add(Rational::ONE), None);
+        assert_eq!(big.checked_mul(Rational::from(2_i64)), None);
+        assert_eq!(Rational::ONE.checked_div(Rational::ZERO), None);
+        assert_eq!(Rational::checked_new(1, 0), None);
+        // Cancelling first keeps this in range
+        assert_eq!(
+            big.checked_mul(Rational::new(1, i128::MAX)),
+            Some(Rational::ONE)
+        );
+        assert!(big > Rational::new(i128::MAX - 1, 1));
+    }
+}
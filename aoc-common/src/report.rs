@@ -0,0 +1,127 @@
+//! Problems found while reading input line by line, so a line that's dropped or read in a
+//! questionable way never changes an answer without anyone noticing.
+
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    /// The line was left out of the answer
+    Skipped,
+    /// The line was used, but could reasonably have been read another way
+    Ambiguous,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Severity::Skipped => write!(f, "skipped"),
+            Severity::Ambiguous => write!(f, "ambiguous"),
+        }
+    }
+}
+
+/// What was wrong with one line. Lines are numbered from 1
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub line: usize,
+    pub severity: Severity,
+    pub reason: String,
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}, {}", self.line, self.severity, self.reason)
+    }
+}
+
+/// Every diagnostic from one run, in the order they were found
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Report {
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl Report {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn skip(&mut self, line: usize, reason: impl ToString) {
+        self.push(line, Severity::Skipped, reason);
+    }
+
+    pub fn ambiguous(&mut self, line: usize, reason: impl ToString) {
+        self.push(line, Severity::Ambiguous, reason);
+    }
+
+    fn push(&mut self, line: usize, severity: Severity, reason: impl ToString) {
+        self.diagnostics.push(Diagnostic {
+            line,
+            severity,
+            reason: reason.to_string(),
+        });
+    }
+
+    /// Moves everything from `later` onto the end
+    pub fn append(&mut self, mut later: Report) {
+        self.diagnostics.append(&mut later.diagnostics);
+    }
+
+    pub fn len(&self) -> usize {
+        self.diagnostics.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.diagnostics.is_empty()
+    }
+
+    /// Number of lines with the given severity
+    pub fn count(&self, severity: Severity) -> usize {
+        self.iter().filter(|d| d.severity == severity).count()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Diagnostic> {
+        self.diagnostics.iter()
+    }
+}
+
+/// One diagnostic per line then a summary, or nothing at all if there were none
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_empty() {
+            return Ok(());
+        }
+        for diagnostic in self.iter() {
+            writeln!(f, "{diagnostic}")?;
+        }
+        writeln!(
+            f,
+            "{} skipped, {} ambiguous",
+            self.count(Severity::Skipped),
+            self.count(Severity::Ambiguous)
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn report_lines() {
+        let mut report = Report::new();
+        assert_eq!(report.to_string(), "");
+
+        report.skip(3, "no digits");
+        report.ambiguous(7, format!("{:?} and {:?} overlap", "eight", "two"));
+        report.skip(9, "no digits");
+        assert_eq!(report.len(), 3);
+        assert_eq!(report.count(Severity::Skipped), 2);
+        assert_eq!(
+            report.to_string(),
+            "line 3: skipped, no digits\n\
+             line 7: ambiguous, \"eight\" and \"two\" overlap\n\
+             line 9: skipped, no digits\n\
+             2 skipped, 1 ambiguous\n"
+        );
+    }
+}
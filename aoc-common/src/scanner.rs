@@ -0,0 +1,204 @@
+//! Finds every occurrence of a set of patterns in one pass over the input, overlapping ones
+//! included, with an Aho–Corasick automaton.
+
+use std::collections::{BTreeMap, VecDeque};
+
+const ROOT: usize = 0;
+
+#[derive(Debug, Clone, Default)]
+struct Node {
+    next: BTreeMap<u8, usize>,
+    /// Longest proper suffix of this node that is also in the trie
+    fail: usize,
+    /// Nearest node down the fail links that ends a pattern, `ROOT` if none
+    output: usize,
+    /// The pattern ending here
+    pattern: Option<usize>,
+}
+
+/// Each pattern is paired with a value that's handed back when it's found
+#[derive(Debug, Clone)]
+pub struct Scanner<V> {
+    nodes: Vec<Node>,
+    /// Length and value of each pattern
+    patterns: Vec<(usize, V)>,
+}
+
+/// A pattern found at `start..end` in the input
+#[derive(Debug, PartialEq, Eq)]
+pub struct Match<'a, V> {
+    pub start: usize,
+    pub end: usize,
+    pub value: &'a V,
+}
+
+impl<V> Clone for Match<'_, V> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<V> Copy for Match<'_, V> {}
+
+impl<V> Scanner<V> {
+    /// Panics on an empty pattern. If a pattern is given twice the last value is kept
+    pub fn new<P: AsRef<[u8]>>(patterns: impl IntoIterator<Item = (P, V)>) -> Self {
+        let mut scanner = Scanner {
+            nodes: vec![Node::default()],
+            patterns: Vec::new(),
+        };
+
+        for (pattern, value) in patterns {
+            let pattern = pattern.as_ref();
+            assert!(!pattern.is_empty(), "can't scan for an empty pattern");
+
+            let mut node = ROOT;
+            for byte in pattern {
+                node = match scanner.nodes[node].next.get(byte) {
+                    Some(next) => *next,
+                    None => {
+                        scanner.nodes.push(Node::default());
+                        let next = scanner.nodes.len() - 1;
+                        scanner.nodes[node].next.insert(*byte, next);
+                        next
+                    }
+                };
+            }
+
+            match scanner.nodes[node].pattern {
+                Some(existing) => scanner.patterns[existing].1 = value,
+                None => {
+                    scanner.nodes[node].pattern = Some(scanner.patterns.len());
+                    scanner.patterns.push((pattern.len(), value));
+                }
+            }
+        }
+
+        scanner.link();
+        scanner
+    }
+
+    /// Fills in the fail and output links, breadth first so shorter suffixes are done first
+    fn link(&mut self) {
+        let mut queue: VecDeque<usize> = self.nodes[ROOT].next.values().copied().collect();
+        while let Some(node) = queue.pop_front() {
+            let children: Vec<(u8, usize)> = self.nodes[node]
+                .next
+                .iter()
+                .map(|(b, n)| (*b, *n))
+                .collect();
+            for (byte, child) in children {
+                let fail = self.step(self.nodes[node].fail, byte);
+                self.nodes[child].fail = fail;
+                self.nodes[child].output = if self.nodes[fail].pattern.is_some() {
+                    fail
+                } else {
+                    self.nodes[fail].output
+                };
+                queue.push_back(child);
+            }
+        }
+    }
+
+    /// Follows `byte` from `node`, falling back along the fail links until it can
+    fn step(&self, mut node: usize, byte: u8) -> usize {
+        loop {
+            if let Some(next) = self.nodes[node].next.get(&byte) {
+                return *next;
+            }
+            if node == ROOT {
+                return ROOT;
+            }
+            node = self.nodes[node].fail;
+        }
+    }
+
+    /// Every match in `haystack`, ordered by where they end. Matches ending at the same
+    /// place come longest first
+    pub fn find_overlapping<'a>(
+        &'a self,
+        haystack: &'a (impl AsRef<[u8]> + ?Sized),
+    ) -> Matches<'a, V> {
+        Matches {
+            scanner: self,
+            haystack: haystack.as_ref(),
+            pos: 0,
+            state: ROOT,
+            output: ROOT,
+        }
+    }
+}
+
+/// Iterator over matches, see `Scanner::find_overlapping`
+pub struct Matches<'a, V> {
+    scanner: &'a Scanner<V>,
+    haystack: &'a [u8],
+    pos: usize,
+    state: usize,
+    /// Next node to report a pattern from at `pos`
+    output: usize,
+}
+
+impl<'a, V> Iterator for Matches<'a, V> {
+    type Item = Match<'a, V>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if self.output != ROOT {
+                let node = &self.scanner.nodes[self.output];
+                self.output = node.output;
+                if let Some(pattern) = node.pattern {
+                    let (len, value) = &self.scanner.patterns[pattern];
+                    return Some(Match {
+                        start: self.pos - len,
+                        end: self.pos,
+                        value,
+                    });
+                }
+                continue;
+            }
+
+            let byte = *self.haystack.get(self.pos)?;
+            self.state = self.scanner.step(self.state, byte);
+            self.pos += 1;
+            self.output = self.state;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn found<'a>(scanner: &'a Scanner<u32>, haystack: &'a str) -> Vec<(usize, u32)> {
+        scanner
+            .find_overlapping(haystack)
+            .map(|m| (m.start, *m.value))
+            .collect()
+    }
+
+    #[test]
+    fn overlapping_digits() {
+        let words = [
+            "one", "two", "three", "four", "five", "six", "seven", "eight", "nine",
+        ];
+        let scanner = Scanner::new(words.iter().zip(1..));
+
+        assert_eq!(
+            found(&scanner, "eightwothree"),
+            vec![(0, 8), (4, 2), (7, 3)]
+        );
+        assert_eq!(found(&scanner, "xtwone3four"), vec![(1, 2), (3, 1), (7, 4)]);
+        assert_eq!(found(&scanner, "oneight"), vec![(0, 1), (2, 8)]);
+        assert_eq!(found(&scanner, "ninine"), vec![(2, 9)]);
+        assert_eq!(found(&scanner, "7pqrst"), vec![]);
+    }
+
+    #[test]
+    fn patterns_inside_each_other() {
+        let scanner = Scanner::new([("he", 1), ("she", 2), ("his", 3), ("hers", 4), ("he", 5)]);
+        let matches: Vec<_> = scanner.find_overlapping("ushers").collect();
+        let found: Vec<_> = matches.iter().map(|m| (m.start, m.end, *m.value)).collect();
+        assert_eq!(found, vec![(1, 4, 2), (2, 4, 5), (2, 6, 4)]);
+    }
+}
@@ -0,0 +1,319 @@
+//! Searches over any graph described by a start state and a successor function that returns
+//! each neighbouring state with the cost of moving there.
+
+use crate::{grid::Grid, point::Point};
+use num_traits::Zero;
+use std::{
+    cmp::Ordering,
+    collections::{BinaryHeap, HashMap, VecDeque},
+    hash::Hash,
+    ops::Add,
+};
+
+/// What a search found, the best cost to each state it reached and how it got there
+#[derive(Debug, Clone)]
+pub struct Search<S, C> {
+    costs: HashMap<S, C>,
+    parents: HashMap<S, S>,
+    goal: Option<S>,
+    visited: usize,
+}
+
+impl<S: Hash + Eq + Clone, C: Copy> Search<S, C> {
+    fn new(start: S, zero: C) -> Self {
+        Search {
+            costs: HashMap::from([(start, zero)]),
+            parents: HashMap::new(),
+            goal: None,
+            visited: 0,
+        }
+    }
+
+    /// The first goal state the search reached, if any
+    pub fn goal(&self) -> Option<&S> {
+        self.goal.as_ref()
+    }
+
+    pub fn goal_cost(&self) -> Option<C> {
+        self.goal.as_ref().and_then(|goal| self.cost(goal))
+    }
+
+    /// Best cost found to `state`. Only guaranteed to be the lowest for states the search
+    /// finished with, which is all of them unless it stopped early at a goal
+    pub fn cost(&self, state: &S) -> Option<C> {
+        self.costs.get(state).copied()
+    }
+
+    /// Every state reached and the best cost found to it
+    pub fn reached(&self) -> impl Iterator<Item = (&S, C)> {
+        self.costs.iter().map(|(state, cost)| (state, *cost))
+    }
+
+    /// Number of states whose successors were expanded
+    pub fn visited(&self) -> usize {
+        self.visited
+    }
+
+    /// States from the start to `state`, including both
+    pub fn path_to(&self, state: &S) -> Option<Vec<S>> {
+        if !self.costs.contains_key(state) {
+            return None;
+        }
+
+        let mut path = vec![state.clone()];
+        while let Some(parent) = self.parents.get(path.last().unwrap()) {
+            path.push(parent.clone());
+        }
+        path.reverse();
+        Some(path)
+    }
+
+    /// States from the start to the goal, including both
+    pub fn path(&self) -> Option<Vec<S>> {
+        self.goal.as_ref().and_then(|goal| self.path_to(goal))
+    }
+}
+
+/// Breadth first search, every edge counts as one step whatever its cost.
+/// Stops at the first state where `is_goal` is true
+pub fn bfs<S, C, FS, I>(
+    start: S,
+    mut successors: FS,
+    mut is_goal: impl FnMut(&S) -> bool,
+) -> Search<S, usize>
+where
+    S: Hash + Eq + Clone,
+    FS: FnMut(&S) -> I,
+    I: IntoIterator<Item = (S, C)>,
+{
+    let mut search = Search::new(start.clone(), 0);
+    let mut queue = VecDeque::from([start]);
+
+    while let Some(state) = queue.pop_front() {
+        if is_goal(&state) {
+            search.goal = Some(state);
+            break;
+        }
+        search.visited += 1;
+
+        let steps = search.costs[&state] + 1;
+        for (next, _) in successors(&state) {
+            if !search.costs.contains_key(&next) {
+                search.costs.insert(next.clone(), steps);
+                search.parents.insert(next.clone(), state.clone());
+                queue.push_back(next);
+            }
+        }
+    }
+
+    search
+}
+
+/// Lowest cost search, edge costs must not be negative.
+/// Stops at the first state where `is_goal` is true
+pub fn dijkstra<S, C, FS, I>(
+    start: S,
+    successors: FS,
+    is_goal: impl FnMut(&S) -> bool,
+) -> Search<S, C>
+where
+    S: Hash + Eq + Clone,
+    C: Copy + Ord + Add<Output = C> + Zero,
+    FS: FnMut(&S) -> I,
+    I: IntoIterator<Item = (S, C)>,
+{
+    best_first(start, successors, |_| C::zero(), is_goal, None)
+}
+
+/// Lowest cost search guided by `heuristic`, which must never overestimate the cost to a goal.
+/// Stops at the first state where `is_goal` is true
+pub fn astar<S, C, FS, I>(
+    start: S,
+    successors: FS,
+    heuristic: impl FnMut(&S) -> C,
+    is_goal: impl FnMut(&S) -> bool,
+) -> Search<S, C>
+where
+    S: Hash + Eq + Clone,
+    C: Copy + Ord + Add<Output = C> + Zero,
+    FS: FnMut(&S) -> I,
+    I: IntoIterator<Item = (S, C)>,
+{
+    best_first(start, successors, heuristic, is_goal, None)
+}
+
+/// Every state that can be reached for at most `max_cost`, see `Search::reached`
+pub fn reachable_within<S, C, FS, I>(start: S, successors: FS, max_cost: C) -> Search<S, C>
+where
+    S: Hash + Eq + Clone,
+    C: Copy + Ord + Add<Output = C> + Zero,
+    FS: FnMut(&S) -> I,
+    I: IntoIterator<Item = (S, C)>,
+{
+    best_first(start, successors, |_| C::zero(), |_| false, Some(max_cost))
+}
+
+/// Entry in the frontier, ordered so the lowest priority comes out of the heap first
+struct Frontier<S, C> {
+    priority: C,
+    cost: C,
+    state: S,
+}
+
+impl<S, C: Ord> Ord for Frontier<S, C> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.priority.cmp(&self.priority)
+    }
+}
+
+impl<S, C: Ord> PartialOrd for Frontier<S, C> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<S, C: Ord> PartialEq for Frontier<S, C> {
+    fn eq(&self, other: &Self) -> bool {
+        self.priority == other.priority
+    }
+}
+
+impl<S, C: Ord> Eq for Frontier<S, C> {}
+
+fn best_first<S, C, FS, I>(
+    start: S,
+    mut successors: FS,
+    mut heuristic: impl FnMut(&S) -> C,
+    mut is_goal: impl FnMut(&S) -> bool,
+    max_cost: Option<C>,
+) -> Search<S, C>
+where
+    S: Hash + Eq + Clone,
+    C: Copy + Ord + Add<Output = C> + Zero,
+    FS: FnMut(&S) -> I,
+    I: IntoIterator<Item = (S, C)>,
+{
+    let mut search = Search::new(start.clone(), C::zero());
+    let mut frontier = BinaryHeap::from([Frontier {
+        priority: heuristic(&start),
+        cost: C::zero(),
+        state: start,
+    }]);
+
+    while let Some(Frontier { cost, state, .. }) = frontier.pop() {
+        if cost > search.costs[&state] {
+            // Already expanded this state with a lower cost
+            continue;
+        }
+        if is_goal(&state) {
+            search.goal = Some(state);
+            break;
+        }
+        search.visited += 1;
+
+        for (next, step) in successors(&state) {
+            let next_cost = cost + step;
+            let too_far = max_cost.is_some_and(|max| next_cost > max);
+            let better = search
+                .costs
+                .get(&next)
+                .is_none_or(|known| next_cost < *known);
+            if better && !too_far {
+                search.costs.insert(next.clone(), next_cost);
+                search.parents.insert(next.clone(), state.clone());
+                frontier.push(Frontier {
+                    priority: next_cost + heuristic(&next),
+                    cost: next_cost,
+                    state: next,
+                });
+            }
+        }
+    }
+
+    search
+}
+
+impl<T> Grid<T> {
+    /// Successors for a search that can step to any neighbour whose cell is `passable`,
+    /// each step costs 1
+    pub fn steps<'a>(
+        &'a self,
+        passable: impl Fn(&T) -> bool + 'a,
+    ) -> impl Fn(&Point) -> Vec<(Point, usize)> + 'a {
+        self.weighted_steps(move |cell| passable(cell).then_some(1))
+    }
+
+    /// Successors for a search where `cost` gives the cost of stepping onto a cell,
+    /// or `None` if it can't be stepped on
+    pub fn weighted_steps<'a, C>(
+        &'a self,
+        cost: impl Fn(&T) -> Option<C> + 'a,
+    ) -> impl Fn(&Point) -> Vec<(Point, C)> + 'a {
+        move |point| {
+            self.neighbours(*point)
+                .filter_map(|next| Some((next, cost(&self[next])?)))
+                .collect()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MAZE: &str = "\
+S..#....
+.#.#.##.
+.#...#..
+.####.#.
+......#E";
+
+    #[test]
+    fn bfs_shortest_path() {
+        let grid: Grid<char> = MAZE.parse().unwrap();
+        let start = grid.find(|c| *c == 'S').unwrap();
+        let end = grid.find(|c| *c == 'E').unwrap();
+
+        let search = bfs(start, grid.steps(|c| *c != '#'), |p| *p == end);
+        let path = search.path().unwrap();
+        assert_eq!(search.goal_cost(), Some(15));
+        assert_eq!(path.len(), 16);
+        assert_eq!((path[0], path[15]), (start, end));
+        assert!(path.windows(2).all(|pair| pair[0].manhattan(pair[1]) == 1));
+        assert!(search.visited() < grid.points().count());
+    }
+
+    #[test]
+    fn dijkstra_and_astar_agree() {
+        let grid = Grid::parse("19111\n19191\n11191\n99991\n11111", |c| c.to_digit(10)).unwrap();
+        let start = Point::new(0, 0);
+        let end = Point::new(4, 0);
+        let successors = grid.weighted_steps(|cell| *cell);
+
+        let slow = dijkstra(start, &successors, |p| *p == end);
+        let fast = astar(
+            start,
+            &successors,
+            |p| p.manhattan(end) as u32,
+            |p| *p == end,
+        );
+        assert_eq!(slow.goal_cost(), Some(12));
+        assert_eq!(fast.goal_cost(), Some(12));
+        assert!(fast.visited() <= slow.visited());
+    }
+
+    #[test]
+    fn reachable_in_steps() {
+        let grid: Grid<char> = MAZE.parse().unwrap();
+        let search = reachable_within(Point::new(0, 0), grid.steps(|c| *c != '#'), 3);
+        let mut reached: Vec<Point> = search.reached().map(|(p, _)| *p).collect();
+        reached.sort();
+        assert_eq!(
+            reached,
+            vec![(0, 0), (0, 1), (0, 2), (1, 0), (1, 2), (2, 0), (3, 0)]
+                .into_iter()
+                .map(Point::from)
+                .collect::<Vec<_>>()
+        );
+    }
+}
@@ -0,0 +1,45 @@
+//! Values of Unicode decimal digits (general category Nd), like `٣` or `３`, which
+//! `char::to_digit` only knows in ASCII.
+
+/// The zero of every run of decimal digits, from the Unicode 14.0 character database.
+/// Unicode promises decimal digits always come in runs of ten from 0 to 9
+const ZEROS: [u32; 66] = [
+    0x0030, 0x0660, 0x06F0, 0x07C0, 0x0966, 0x09E6, 0x0A66, 0x0AE6, 0x0B66, 0x0BE6, 0x0C66, 0x0CE6,
+    0x0D66, 0x0DE6, 0x0E50, 0x0ED0, 0x0F20, 0x1040, 0x1090, 0x17E0, 0x1810, 0x1946, 0x19D0, 0x1A80,
+    0x1A90, 0x1B50, 0x1BB0, 0x1C40, 0x1C50, 0xA620, 0xA8D0, 0xA900, 0xA9D0, 0xA9F0, 0xAA50, 0xABF0,
+    0xFF10, 0x104A0, 0x10D30, 0x11066, 0x110F0, 0x11136, 0x111D0, 0x112F0, 0x11450, 0x114D0,
+    0x11650, 0x116C0, 0x11730, 0x118E0, 0x11950, 0x11C50, 0x11D50, 0x11DA0, 0x16A60, 0x16AC0,
+    0x16B50, 0x1D7CE, 0x1D7D8, 0x1D7E2, 0x1D7EC, 0x1D7F6, 0x1E140, 0x1E2F0, 0x1E950, 0x1FBF0,
+];
+
+/// The value of `c` if it's a decimal digit in any script
+pub fn decimal_value(c: char) -> Option<u32> {
+    let c = c as u32;
+    let zero = match ZEROS.binary_search(&c) {
+        Ok(found) => ZEROS[found],
+        Err(0) => return None,
+        Err(after) => ZEROS[after - 1],
+    };
+    (c - zero < 10).then_some(c - zero)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn digits_in_other_scripts() {
+        let digits = |s: &str| -> Vec<Option<u32>> { s.chars().map(decimal_value).collect() };
+        assert_eq!(digits("09"), vec![Some(0), Some(9)]);
+        // Full width, Arabic-Indic, Devanagari, Bengali, Thai and mathematical bold
+        assert_eq!(digits("１２３"), vec![Some(1), Some(2), Some(3)]);
+        assert_eq!(digits("٤٥"), vec![Some(4), Some(5)]);
+        assert_eq!(digits("७৮๙"), vec![Some(7), Some(8), Some(9)]);
+        assert_eq!(digits("𝟎𝟗"), vec![Some(0), Some(9)]);
+
+        // Numbers that aren't decimal digits
+        assert_eq!(digits("a/²½Ⅻ①"), vec![None; 6]);
+        assert_eq!(decimal_value('\u{1FBFA}'), None);
+        assert_eq!(decimal_value('\u{2F}'), None);
+    }
+}
@@ -1,40 +1,75 @@
-use clap::Parser;
-use std::{
-    fs::File,
-    io::{self, BufRead},
-    path::{Path, PathBuf},
+use aoc_common::{
+    checked::Overflow,
+    lines::{sum_lines, Document},
+    report::Report,
+    unicode,
 };
+use clap::Parser;
+use std::path::{Path, PathBuf};
 
 #[derive(Parser)]
 #[command(author, version, about, long_about = None)]
 struct Cli {
     input: PathBuf,
+    /// Fail instead of leaving out lines that can't be read
+    #[arg(long)]
+    strict: bool,
+    /// Understand decimal digits from any script, like `３` or `٣`, not just ASCII ones
+    #[arg(long)]
+    unicode: bool,
 }
 
 fn main() {
-    let input_filename = Cli::parse().input;
-    let mut sum: u32 = 0;
-    for line in read_lines(input_filename).unwrap() {
-        sum += parse_line(&line.unwrap());
-    }
+    let cli = Cli::parse();
+    let (sum, report) = calibrate(&cli.input, cli.unicode);
+    let sum = sum.expect("answer is too big");
 
+    eprint!("{report}");
+    if cli.strict && !report.is_empty() {
+        eprintln!("Giving up, some lines couldn't be read");
+        std::process::exit(1);
+    }
     println!("Final: {sum}");
 }
 
-fn parse_line(line: &str) -> u32 {
-    let digits: Vec<char> = line.chars().filter(|&c| c.is_ascii_digit()).collect();
+fn calibrate(path: &Path, unicode: bool) -> (Result<u64, Overflow>, Report) {
+    let document = Document::open(path).unwrap();
+    sum_lines(document.bytes(), |number, line, report| {
+        let value = parse_line(line, unicode);
+        if value.is_none() {
+            report.skip(number, "no digits");
+        }
+        value
+    })
+}
 
-    let mut full_number = digits[0].to_string();
-    full_number.push(digits[digits.len() - 1]);
-    full_number.parse().unwrap()
+/// `None` if the line has no digits. Combining marks on a digit, like the keycap in `1⃣`,
+/// are passed over since they aren't digits themselves
+fn parse_line(line: &str, unicode: bool) -> Option<u64> {
+    let value = if unicode {
+        unicode::decimal_value
+    } else {
+        |c: char| c.to_digit(10)
+    };
+    let mut digits = line.chars().filter_map(value);
+    let first = digits.next()?;
+    let last = digits.next_back().unwrap_or(first);
+    Some((first * 10 + last).into())
 }
 
-// The output is wrapped in a Result to allow matching on errors
-// Returns an Iterator to the Reader of the lines of the file.
-fn read_lines<P>(filename: P) -> io::Result<io::Lines<io::BufReader<File>>>
-where
-    P: AsRef<Path>,
-{
-    let file = File::open(filename)?;
-    Ok(io::BufReader::new(file).lines())
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn multilingual_digits() {
+        let (sum, report) = calibrate(Path::new("tests/multilingual"), true);
+        assert_eq!(sum, Ok(13 + 37 + 59 + 14 + 22 + 23 + 78));
+        assert_eq!(report.iter().map(|d| d.line).collect::<Vec<_>>(), vec![7]);
+
+        // Only the keycaps, the accented line and the 7 are found without it
+        let (sum, report) = calibrate(Path::new("tests/multilingual"), false);
+        assert_eq!(sum, Ok(14 + 22 + 77));
+        assert_eq!(report.len(), 5);
+    }
 }
@@ -0,0 +1,189 @@
+//! Reads each line with numerals only, like part 1, and again with words, like part 2, and
+//! lays the two side by side so it's easy to see where and why they disagree.
+
+use crate::{calibration_value, vocabulary::Vocabulary, Combine, Reader, Token};
+use std::fmt;
+
+/// How one reader read a line
+#[derive(Debug, Clone, PartialEq)]
+pub struct Reading {
+    pub value: Option<u64>,
+    /// The text of the tokens that made the value
+    pub tokens: Vec<String>,
+}
+
+impl Reading {
+    fn new(reader: &Reader, combine: Combine, line: &str) -> Self {
+        let Ok(tokens) = reader.tokens(line) else {
+            return Reading {
+                value: None,
+                tokens: Vec::new(),
+            };
+        };
+        let used: Vec<&Token> = match (combine, tokens.first(), tokens.last()) {
+            (Combine::FirstLast, Some(first), Some(last)) if first == last => vec![first],
+            (Combine::FirstLast, Some(first), Some(last)) => vec![first, last],
+            _ => tokens.iter().collect(),
+        };
+        Reading {
+            value: calibration_value(&tokens, combine).ok(),
+            tokens: used
+                .iter()
+                .map(|token| line[token.start..token.end].to_string())
+                .collect(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Row {
+    /// From 1
+    pub number: usize,
+    pub line: String,
+    pub digits: Reading,
+    pub words: Reading,
+}
+
+impl Row {
+    pub fn differs(&self) -> bool {
+        self.digits.value != self.words.value
+    }
+}
+
+/// One row per line, printed as a table with the lines that differ marked by a `*`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Explanation {
+    pub rows: Vec<Row>,
+}
+
+impl Explanation {
+    /// Compares `words` with a reader that only knows the numerals every vocabulary has
+    pub fn new(words: &Reader, combine: Combine, text: &str) -> Self {
+        let digits = Reader::Digits(Vocabulary::numerals().scanner());
+        let rows = text
+            .lines()
+            .zip(1..)
+            .map(|(line, number)| Row {
+                number,
+                line: line.to_string(),
+                digits: Reading::new(&digits, combine, line),
+                words: Reading::new(words, combine, line),
+            })
+            .collect();
+        Explanation { rows }
+    }
+
+    fn total(&self, reading: impl Fn(&Row) -> &Reading) -> Option<u64> {
+        self.rows
+            .iter()
+            .filter_map(|row| reading(row).value)
+            .try_fold(0_u64, |sum, value| sum.checked_add(value))
+    }
+}
+
+fn cells(reading: &Reading) -> [String; 2] {
+    let value = reading
+        .value
+        .map_or("-".to_string(), |value| value.to_string());
+    [value, reading.tokens.join(" ")]
+}
+
+impl fmt::Display for Explanation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let header = ["line", "raw", "digits", "tokens", "words", "tokens"].map(String::from);
+        let table: Vec<(bool, [String; 6])> = self
+            .rows
+            .iter()
+            .map(|row| {
+                let [digits, digit_tokens] = cells(&row.digits);
+                let [words, word_tokens] = cells(&row.words);
+                let cells = [
+                    row.number.to_string(),
+                    row.line.clone(),
+                    digits,
+                    digit_tokens,
+                    words,
+                    word_tokens,
+                ];
+                (row.differs(), cells)
+            })
+            .collect();
+
+        let mut widths = header.clone().map(|cell| cell.chars().count());
+        for (_, cells) in &table {
+            for (width, cell) in widths.iter_mut().zip(cells) {
+                *width = (*width).max(cell.chars().count());
+            }
+        }
+
+        for (differs, cells) in [(false, header)].iter().chain(&table) {
+            let mut row = String::from(if *differs { "*" } else { " " });
+            for (idx, (cell, width)) in cells.iter().zip(widths).enumerate() {
+                // Numbers line up on the right, text on the left
+                let padding = " ".repeat(width - cell.chars().count());
+                match idx {
+                    0 | 2 | 4 => row += &format!(" {padding}{cell}"),
+                    _ => row += &format!(" {cell}{padding}"),
+                }
+            }
+            writeln!(f, "{}", row.trim_end())?;
+        }
+
+        let total = |total: Option<u64>| total.map_or("too big".to_string(), |t| t.to_string());
+        let differ = self.rows.iter().filter(|row| row.differs()).count();
+        writeln!(
+            f,
+            "Digits only: {}, with words: {}, {differ} of {} lines differ",
+            total(self.total(|row| &row.digits)),
+            total(self.total(|row| &row.words)),
+            self.rows.len()
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn eightwo() {
+        let words = Reader::Digits(Vocabulary::default().scanner());
+        let text = "1abc2\neightwo\nxtwone3four\nabc";
+        let explanation = Explanation::new(&words, Combine::FirstLast, text);
+
+        let differs: Vec<bool> = explanation.rows.iter().map(Row::differs).collect();
+        assert_eq!(differs, vec![false, true, true, false]);
+        assert_eq!(
+            explanation.rows[2].words,
+            Reading {
+                value: Some(24),
+                tokens: vec!["two".to_string(), "four".to_string()],
+            }
+        );
+        let table = [
+            "  line raw         digits tokens words tokens",
+            "     1 1abc2           12 1 2       12 1 2",
+            "*    2 eightwo          -           82 eight two",
+            "*    3 xtwone3four     33 3         24 two four",
+            "     4 abc              -            -",
+            "Digits only: 45, with words: 118, 2 of 4 lines differ",
+            "",
+        ];
+        assert_eq!(explanation.to_string(), table.join("\n"));
+    }
+
+    #[test]
+    fn zero_is_not_a_difference() {
+        // Neither reader knows 0, so lines with one only differ if the words do
+        let words = Reader::Digits(Vocabulary::default().scanner());
+        let explanation = Explanation::new(
+            &words,
+            Combine::FirstLast,
+            "0a5b70
+0one",
+        );
+        let differs: Vec<bool> = explanation.rows.iter().map(Row::differs).collect();
+        assert_eq!(differs, vec![false, true]);
+        assert_eq!(explanation.rows[0].digits.value, Some(57));
+    }
+}
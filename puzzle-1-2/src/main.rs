@@ -1,76 +1,286 @@
-use clap::Parser;
-use regex::Regex;
-use std::{
-    fs::File,
-    io::{self, BufRead},
-    path::{Path, PathBuf},
+mod explain;
+mod numbers;
+mod vocabulary;
+
+use aoc_common::{
+    checked::CheckedIterator,
+    lines::{sum_lines, Document},
+    scanner::Scanner,
 };
+use clap::Parser;
+use explain::Explanation;
+use numbers::{NumberReader, Token, TooBigErr};
+use std::path::PathBuf;
+use vocabulary::{Preset, Vocabulary};
 
 #[derive(Parser)]
 #[command(author, version, about, long_about = None)]
 struct Cli {
     input: PathBuf,
+    /// Built in vocabulary of number words
+    #[arg(long, value_enum, default_value_t)]
+    language: Preset,
+    /// File of `token = digit` lines to use instead of the built in vocabulary
+    #[arg(long, conflicts_with = "language")]
+    vocabulary: Option<PathBuf>,
+    /// Extra `token=digit` to understand, can be given more than once
+    #[arg(long = "word", value_parser = vocabulary::parse_word)]
+    words: Vec<(String, u32)>,
+    /// Read whole numbers (`123`, `twenty-one`) instead of single digits. English only
+    #[arg(long, conflicts_with_all = ["language", "vocabulary", "words"])]
+    numbers: bool,
+    /// How to turn the numbers in a line into its calibration value
+    #[arg(long, value_enum, default_value_t)]
+    combine: Combine,
+    /// Fail instead of leaving out lines that can't be read or reading overlapping words
+    #[arg(long)]
+    strict: bool,
+    /// Print how each line reads with numerals only and with words, instead of the total
+    #[arg(long)]
+    explain: bool,
 }
 
-fn main() {
-    let input_filename = Cli::parse().input;
-    let mut sum: u32 = 0;
-    for line in read_lines(input_filename).unwrap() {
-        let line2 = line.unwrap();
-        if let Ok(num) = parse_line(&line2) {
-            sum += num;
-        } else {
-            println!("Error parsing {line2}");
+/// How to find the numbers in a line
+enum Reader {
+    /// Single digits, written as numerals or words from a vocabulary. Words can overlap
+    Digits(Scanner<u32>),
+    Numbers(NumberReader),
+}
+
+impl Reader {
+    /// Every number in the line, in the order they start. Fails if a number is too big
+    fn tokens(&self, line: &str) -> Result<Vec<Token>, TooBigErr> {
+        match self {
+            Reader::Digits(scanner) => {
+                // Words can overlap (eightwo), the scanner finds both so there's no need to
+                // search backwards
+                let mut tokens: Vec<Token> = scanner
+                    .find_overlapping(line)
+                    .map(|found| Token {
+                        start: found.start,
+                        end: found.end,
+                        value: *found.value as u64,
+                    })
+                    .collect();
+                tokens.sort_by_key(|token| (token.start, std::cmp::Reverse(token.end)));
+                Ok(tokens)
+            }
+            Reader::Numbers(reader) => reader.numbers(line),
         }
     }
+}
 
-    println!("Final: {sum}");
+#[derive(clap::ValueEnum, Debug, Clone, Copy, Default, PartialEq)]
+enum Combine {
+    /// The first number followed by the last one, `7` and `21` make 721
+    #[default]
+    FirstLast,
+    /// All the numbers added up
+    Sum,
+    /// All the numbers written one after another
+    Concat,
+}
+
+impl Combine {
+    /// `None` if the value doesn't fit in a u64
+    fn apply(&self, values: &[u64]) -> Option<u64> {
+        match self {
+            Combine::FirstLast => concat(*values.first()?, *values.last()?),
+            Combine::Sum => values.iter().copied().checked_sum().ok(),
+            Combine::Concat => values.iter().try_fold(0, |acc, value| concat(acc, *value)),
+        }
+    }
 }
 
-fn parse_line(line: &str) -> anyhow::Result<u32> {
-    let re = Regex::new(r"([1-9]|one|two|three|four|five|six|seven|eight|nine)").unwrap();
-    let re2 = Regex::new(r"([1-9]|eno|owt|eerht|ruof|evif|xis|neves|thgie|enin)").unwrap();
-    let matches: Vec<_> = re.find_iter(line).map(|m| m.as_str()).collect();
-    let line2: String = line.to_owned().chars().rev().collect();
-    let matches2: Vec<_> = re2.find_iter(&line2).map(|m| m.as_str()).collect();
-
-    let a = parse_number(matches[0])?;
-    let fuck: String = matches2[0].chars().rev().collect();
-    let b = parse_number(&fuck)?;
-    let rtn = (a * 10) + b;
-    // println!("a: {a}, b: {b}, rtn: {rtn}");
-    Ok(rtn)
+/// `a` followed by the digits of `b`
+fn concat(a: u64, b: u64) -> Option<u64> {
+    let digits = b.checked_ilog10().unwrap_or(0) + 1;
+    a.checked_mul(10_u64.checked_pow(digits)?)?.checked_add(b)
 }
 
-fn parse_number(number: &str) -> anyhow::Result<u32> {
-    if number.len() == 1 {
-        Ok(number.parse()?)
+fn main() {
+    let cli = Cli::parse();
+    let mut vocabulary = match &cli.vocabulary {
+        Some(path) => std::fs::read_to_string(path).unwrap().parse().unwrap(),
+        None => Vocabulary::preset(cli.language),
+    };
+    for (token, digit) in cli.words {
+        vocabulary.insert(token, digit);
+    }
+
+    let reader = if cli.numbers {
+        Reader::Numbers(NumberReader::default())
     } else {
-        match number {
-            "one" => Ok(1),
-            "two" => Ok(2),
-            "three" => Ok(3),
-            "four" => Ok(4),
-            "five" => Ok(5),
-            "six" => Ok(6),
-            "seven" => Ok(7),
-            "eight" => Ok(8),
-            "nine" => Ok(9),
-            _ => Err(ReneError)?,
+        Reader::Digits(vocabulary.scanner())
+    };
+    let document = Document::open(cli.input).unwrap();
+    if cli.explain {
+        let text = String::from_utf8_lossy(document.bytes());
+        print!("{}", Explanation::new(&reader, cli.combine, &text));
+        return;
+    }
+
+    let (sum, report) = sum_lines(document.bytes(), |number, line, report| {
+        let tokens = match reader.tokens(line) {
+            Ok(tokens) => tokens,
+            Err(err) => {
+                report.skip(number, err);
+                return None;
+            }
+        };
+        if let Some((a, b)) = overlap(&tokens, cli.combine) {
+            let (a, b) = (&line[a.start..a.end], &line[b.start..b.end]);
+            report.ambiguous(number, format!("{a:?} and {b:?} overlap, both were read"));
         }
+        calibration_value(&tokens, cli.combine)
+            .map_err(|err| report.skip(number, err))
+            .ok()
+    });
+    let sum = sum.expect("answer is too big");
+
+    eprint!("{report}");
+    if cli.strict && !report.is_empty() {
+        eprintln!("Giving up, some lines couldn't be read or were ambiguous");
+        std::process::exit(1);
     }
+    println!("Final: {sum}");
+}
+
+fn calibration_value(tokens: &[Token], combine: Combine) -> anyhow::Result<u64> {
+    let values: Vec<u64> = tokens.iter().map(|token| token.value).collect();
+    if values.is_empty() {
+        Err(NoDigitsError)?
+    }
+
+    Ok(combine.apply(&values).ok_or(TooBigError)?)
 }
 
+/// The first two tokens that share some of the line, like `eight` and `two` in `eightwo`,
+/// where it changes the value. Reading left to right without overlaps would drop the later
+/// token, which only matters if it's used. Taking the first and last number only uses the
+/// last one that way, so `xtwone3four` is 24 however its middle is read
+fn overlap(tokens: &[Token], combine: Combine) -> Option<(Token, Token)> {
+    let last = tokens.len().checked_sub(1)?;
+    (0..tokens.len())
+        .flat_map(|a| (a + 1..tokens.len()).map(move |b| (a, b)))
+        .filter(|(_, b)| combine != Combine::FirstLast || *b == last)
+        .find(|(a, b)| tokens[*b].start < tokens[*a].end)
+        .map(|(a, b)| (tokens[a], tokens[b]))
+}
+
+#[derive(thiserror::Error, Debug, Clone)]
+#[error("no digits")]
+struct NoDigitsError;
+
 #[derive(thiserror::Error, Debug, Clone)]
-#[error("rene error")]
-struct ReneError;
-
-// The output is wrapped in a Result to allow matching on errors
-// Returns an Iterator to the Reader of the lines of the file.
-fn read_lines<P>(filename: P) -> io::Result<io::Lines<io::BufReader<File>>>
-where
-    P: AsRef<Path>,
-{
-    let file = File::open(filename)?;
-    Ok(io::BufReader::new(file).lines())
+#[error("calibration value is too big")]
+struct TooBigError;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use vocabulary::VocabularyErr;
+
+    fn parse_line(reader: &Reader, combine: Combine, line: &str) -> anyhow::Result<u64> {
+        calibration_value(&reader.tokens(line)?, combine)
+    }
+
+    fn first_last(reader: &Reader, line: &str) -> u64 {
+        parse_line(reader, Combine::FirstLast, line).unwrap()
+    }
+
+    #[test]
+    fn overlapping_words() {
+        let reader = Reader::Digits(Vocabulary::default().scanner());
+        let lines = [
+            ("two1nine", 29),
+            ("eightwothree", 83),
+            ("abcone2threexyz", 13),
+            ("xtwone3four", 24),
+            ("4nineeightseven2", 42),
+            ("zoneight234", 14),
+            ("7pqrstsixteen", 76),
+            ("eightwo", 82),
+            ("oneight", 18),
+            ("5", 55),
+        ];
+        for (line, value) in lines {
+            assert_eq!(first_last(&reader, line), value, "{line}");
+        }
+        assert!(parse_line(&reader, Combine::FirstLast, "abc").is_err());
+    }
+
+    #[test]
+    fn other_vocabularies() {
+        let french = Reader::Digits(Vocabulary::preset(Preset::French).scanner());
+        assert_eq!(first_last(&french, "xhuitroisdeux"), 82);
+        assert_eq!(first_last(&french, "neufive"), 99);
+
+        let german = Reader::Digits(Vocabulary::preset(Preset::German).scanner());
+        assert_eq!(first_last(&german, "fünfzehn3sieben"), 57);
+
+        let custom: Vocabulary = "# Roman numerals\nI = 1\nV=5\n\nX = 0".parse().unwrap();
+        let custom = Reader::Digits(custom.scanner());
+        assert_eq!(first_last(&custom, "aXbVIc"), 1);
+        assert_eq!(first_last(&custom, "3one"), 33);
+
+        assert_eq!(
+            "I = 1\nV".parse::<Vocabulary>(),
+            Err(VocabularyErr::BadLine(2, "V".to_string()))
+        );
+        assert_eq!(
+            vocabulary::parse_word("ten=10"),
+            Err(VocabularyErr::BadDigit(1, "10".to_string()))
+        );
+    }
+
+    #[test]
+    fn whole_numbers() {
+        let reader = Reader::Numbers(NumberReader::default());
+        let line = "7 and twenty-one, one hundred5";
+        assert_eq!(first_last(&reader, line), 75);
+        assert_eq!(parse_line(&reader, Combine::Sum, line).unwrap(), 133);
+        assert_eq!(parse_line(&reader, Combine::Concat, line).unwrap(), 7211005);
+        assert_eq!(first_last(&reader, "x twelve y"), 1212);
+        assert_eq!(first_last(&reader, "eightwo"), 88);
+
+        // Single digits still combine the same way
+        let digits = Reader::Digits(Vocabulary::default().scanner());
+        assert_eq!(
+            parse_line(&digits, Combine::Sum, "eightwothree").unwrap(),
+            13
+        );
+        assert_eq!(
+            parse_line(&digits, Combine::Concat, "eightwothree").unwrap(),
+            823
+        );
+
+        assert!(parse_line(&reader, Combine::Concat, "18446744073709551615 1").is_err());
+        // Too long to read at all, rather than read as the biggest u64
+        assert!(parse_line(&reader, Combine::Sum, "a 18446744073709551616 b").is_err());
+        assert!(parse_line(&reader, Combine::Sum, "nothing here").is_err());
+    }
+
+    #[test]
+    fn overlapping_tokens() {
+        let reader = Reader::Digits(Vocabulary::default().scanner());
+        let found = |line: &'static str, combine| {
+            overlap(&reader.tokens(line).unwrap(), combine)
+                .map(|(a, b)| (&line[a.start..a.end], &line[b.start..b.end]))
+        };
+        assert_eq!(
+            found("1eightwo", Combine::FirstLast),
+            Some(("eight", "two"))
+        );
+        assert_eq!(found("4twone", Combine::FirstLast), Some(("two", "one")));
+        assert_eq!(found("twone3", Combine::FirstLast), None);
+        assert_eq!(found("two1nine", Combine::FirstLast), None);
+        // Only the middle overlaps, which doesn't change the first or last digit
+        assert_eq!(found("xtwone3four", Combine::FirstLast), None);
+        assert_eq!(found("xtwone3four", Combine::Sum), Some(("two", "one")));
+
+        let reader = Reader::Numbers(NumberReader::default());
+        let tokens = reader.tokens("eightwo").unwrap();
+        assert_eq!(overlap(&tokens, Combine::FirstLast), None);
+    }
 }
@@ -0,0 +1,243 @@
+use aoc_common::scanner::Scanner;
+
+/// A number read from a line, from bytes `start..end`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Token {
+    pub start: usize,
+    pub end: usize,
+    pub value: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Word {
+    /// zero to nineteen
+    Small(u64),
+    /// twenty, thirty...
+    Tens(u64),
+    Hundred,
+    /// thousand, million, billion
+    Scale(u64),
+}
+
+const SMALL: [&str; 20] = [
+    "zero",
+    "one",
+    "two",
+    "three",
+    "four",
+    "five",
+    "six",
+    "seven",
+    "eight",
+    "nine",
+    "ten",
+    "eleven",
+    "twelve",
+    "thirteen",
+    "fourteen",
+    "fifteen",
+    "sixteen",
+    "seventeen",
+    "eighteen",
+    "nineteen",
+];
+
+const TENS: [&str; 8] = [
+    "twenty", "thirty", "forty", "fifty", "sixty", "seventy", "eighty", "ninety",
+];
+
+/// A run of digits too long for a u64
+#[derive(thiserror::Error, Debug, Clone, PartialEq)]
+#[error("{0} is too big to read")]
+pub struct TooBigErr(pub String);
+
+/// Reads whole numbers, runs of digits like `123` or English words like `twenty-one` and
+/// `one hundred`. Unlike single digits, words are read left to right without overlapping,
+/// taking the longest word at each point, so `eightwo` is just 8
+pub struct NumberReader {
+    words: Scanner<Word>,
+}
+
+impl Default for NumberReader {
+    fn default() -> Self {
+        let small = SMALL.iter().zip(0..).map(|(w, n)| (*w, Word::Small(n)));
+        let tens = TENS.iter().zip(2..).map(|(w, n)| (*w, Word::Tens(n * 10)));
+        let scales = [
+            ("hundred", Word::Hundred),
+            ("thousand", Word::Scale(1_000)),
+            ("million", Word::Scale(1_000_000)),
+            ("billion", Word::Scale(1_000_000_000)),
+        ];
+        NumberReader {
+            words: Scanner::new(small.chain(tens).chain(scales)),
+        }
+    }
+}
+
+/// A number being built up from words
+struct Compound {
+    start: usize,
+    end: usize,
+    /// Everything before the last thousand, million...
+    total: u64,
+    /// Since the last thousand, million...
+    current: u64,
+    last: Word,
+    /// Scales have to get smaller, one million two thousand
+    last_scale: u64,
+}
+
+impl Compound {
+    fn new(start: usize, end: usize, word: Word) -> Self {
+        let mut compound = Compound {
+            start,
+            end,
+            total: 0,
+            current: 0,
+            last: word,
+            last_scale: u64::MAX,
+        };
+        match word {
+            Word::Small(n) | Word::Tens(n) => compound.current = n,
+            Word::Hundred => compound.current = 100,
+            Word::Scale(scale) => {
+                compound.total = scale;
+                compound.last_scale = scale;
+            }
+        }
+        compound
+    }
+
+    /// Adds the next word, or returns false if it starts a new number
+    fn push(&mut self, end: usize, word: Word) -> bool {
+        let follows_scale = matches!(self.last, Word::Hundred | Word::Scale(_));
+        let fits = match word {
+            Word::Small(n) => {
+                follows_scale || (matches!(self.last, Word::Tens(_)) && n < 10 && n > 0)
+            }
+            Word::Tens(_) => follows_scale,
+            Word::Hundred => {
+                matches!(self.last, Word::Small(n) | Word::Tens(n) if n > 0) && self.current < 100
+            }
+            Word::Scale(scale) => self.current > 0 && scale < self.last_scale,
+        };
+        if !fits {
+            return false;
+        }
+
+        match word {
+            Word::Small(n) | Word::Tens(n) => self.current += n,
+            Word::Hundred => self.current *= 100,
+            Word::Scale(scale) => {
+                self.total += self.current * scale;
+                self.current = 0;
+                self.last_scale = scale;
+            }
+        }
+        self.last = word;
+        self.end = end;
+        true
+    }
+
+    fn token(&self) -> Token {
+        Token {
+            start: self.start,
+            end: self.end,
+            value: self.total + self.current,
+        }
+    }
+}
+
+impl NumberReader {
+    pub fn numbers(&self, line: &str) -> Result<Vec<Token>, TooBigErr> {
+        // Longest word starting at each byte
+        let mut longest = vec![None; line.len()];
+        for found in self.words.find_overlapping(line) {
+            let best: &mut Option<(usize, Word)> = &mut longest[found.start];
+            if best.is_none_or(|(end, _)| found.end > end) {
+                *best = Some((found.end, *found.value));
+            }
+        }
+
+        let bytes = line.as_bytes();
+        let mut tokens = Vec::new();
+        let mut compound: Option<Compound> = None;
+        let mut pos = 0;
+        while pos < bytes.len() {
+            if bytes[pos].is_ascii_digit() {
+                let end = pos
+                    + bytes[pos..]
+                        .iter()
+                        .take_while(|b| b.is_ascii_digit())
+                        .count();
+                tokens.extend(compound.take().map(|c| c.token()));
+                let digits = &line[pos..end];
+                let value = digits.parse().map_err(|_| TooBigErr(digits.to_string()))?;
+                tokens.push(Token {
+                    start: pos,
+                    end,
+                    value,
+                });
+                pos = end;
+            } else if let Some((end, word)) = longest[pos] {
+                let joined = compound.as_mut().is_some_and(|c| c.push(end, word));
+                if !joined {
+                    tokens.extend(compound.take().map(|c| c.token()));
+                    compound = Some(Compound::new(pos, end, word));
+                }
+                pos = end;
+            } else {
+                // Spaces and hyphens can sit inside a number, anything else ends it
+                if !matches!(bytes[pos], b' ' | b'-') {
+                    tokens.extend(compound.take().map(|c| c.token()));
+                }
+                pos += 1;
+            }
+        }
+        tokens.extend(compound.map(|c| c.token()));
+        Ok(tokens)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn values(line: &str) -> Vec<u64> {
+        NumberReader::default()
+            .numbers(line)
+            .unwrap()
+            .iter()
+            .map(|t| t.value)
+            .collect()
+    }
+
+    #[test]
+    fn compound_words() {
+        assert_eq!(values("twelve"), vec![12]);
+        assert_eq!(values("xtwenty-one7"), vec![21, 7]);
+        assert_eq!(values("one hundred"), vec![100]);
+        assert_eq!(values("ninehundredninetynine"), vec![999]);
+        assert_eq!(values("two thousand and twenty-three"), vec![2000, 23]);
+        assert_eq!(values("one million two thousand three"), vec![1_002_003]);
+        assert_eq!(values("onetwo three"), vec![1, 2, 3]);
+        assert_eq!(values("twenty thirty"), vec![20, 30]);
+        assert_eq!(values("seventeenine"), vec![17]);
+        assert_eq!(values("eightwo"), vec![8]);
+        assert_eq!(values("a 123 b 45six"), vec![123, 45, 6]);
+        assert_eq!(values("hundred"), vec![100]);
+
+        let tokens = NumberReader::default().numbers("ab fifty-five!").unwrap();
+        assert_eq!(
+            tokens,
+            vec![Token {
+                start: 3,
+                end: 13,
+                value: 55
+            }]
+        );
+
+        let too_big = NumberReader::default().numbers("1 99999999999999999999 2");
+        assert_eq!(too_big, Err(TooBigErr("99999999999999999999".to_string())));
+    }
+}
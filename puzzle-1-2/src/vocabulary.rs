@@ -0,0 +1,118 @@
+use aoc_common::scanner::Scanner;
+use std::str::FromStr;
+
+/// Built in vocabularies for calibration documents written in other languages
+#[derive(clap::ValueEnum, Debug, Clone, Copy, Default, PartialEq)]
+pub enum Preset {
+    #[default]
+    English,
+    French,
+    German,
+    Spanish,
+}
+
+impl Preset {
+    /// The words for 1 to 9
+    fn words(&self) -> [&'static str; 9] {
+        match self {
+            Preset::English => [
+                "one", "two", "three", "four", "five", "six", "seven", "eight", "nine",
+            ],
+            Preset::French => [
+                "un", "deux", "trois", "quatre", "cinq", "six", "sept", "huit", "neuf",
+            ],
+            Preset::German => [
+                "eins", "zwei", "drei", "vier", "fünf", "sechs", "sieben", "acht", "neun",
+            ],
+            Preset::Spanish => [
+                "uno", "dos", "tres", "cuatro", "cinco", "seis", "siete", "ocho", "nueve",
+            ],
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, thiserror::Error)]
+pub enum VocabularyErr {
+    #[error("line {0}: expected `token = digit`, found {1:?}")]
+    BadLine(usize, String),
+    #[error("line {0}: {1:?} isn't a digit from 0 to 9")]
+    BadDigit(usize, String),
+}
+
+/// The tokens that stand for each digit. The numerals 1 to 9 are always understood
+#[derive(Debug, Clone, PartialEq)]
+pub struct Vocabulary {
+    words: Vec<(String, u32)>,
+}
+
+impl Vocabulary {
+    pub fn preset(preset: Preset) -> Self {
+        Vocabulary {
+            words: preset
+                .words()
+                .iter()
+                .map(|w| w.to_string())
+                .zip(1..)
+                .collect(),
+        }
+    }
+
+    /// No words, just the numerals
+    pub fn numerals() -> Self {
+        Vocabulary { words: Vec::new() }
+    }
+
+    /// Adds a token, replacing its digit if it's already there
+    pub fn insert(&mut self, token: String, digit: u32) {
+        self.words.retain(|(word, _)| *word != token);
+        self.words.push((token, digit));
+    }
+
+    pub fn scanner(&self) -> Scanner<u32> {
+        let numerals = (1..=9).map(|digit: u32| (digit.to_string(), digit));
+        Scanner::new(numerals.chain(self.words.iter().cloned()))
+    }
+}
+
+impl Default for Vocabulary {
+    fn default() -> Self {
+        Vocabulary::preset(Preset::default())
+    }
+}
+
+/// Parses a `token=digit` pair, as given on the command line
+pub fn parse_word(s: &str) -> Result<(String, u32), VocabularyErr> {
+    parse_entry(1, s)
+}
+
+fn parse_entry(line: usize, s: &str) -> Result<(String, u32), VocabularyErr> {
+    let (token, digit) = s
+        .split_once('=')
+        .map(|(token, digit)| (token.trim(), digit.trim()))
+        .filter(|(token, _)| !token.is_empty())
+        .ok_or_else(|| VocabularyErr::BadLine(line, s.to_string()))?;
+
+    match digit.parse() {
+        Ok(value @ 0..=9) => Ok((token.to_string(), value)),
+        _ => Err(VocabularyErr::BadDigit(line, digit.to_string())),
+    }
+}
+
+/// A vocabulary file has one `token = digit` per line. Blank lines and lines starting with
+/// `#` are skipped. Only the tokens in the file are used, on top of the numerals
+impl FromStr for Vocabulary {
+    type Err = VocabularyErr;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut vocabulary = Vocabulary::numerals();
+        for (idx, line) in s.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (token, digit) = parse_entry(idx + 1, line)?;
+            vocabulary.insert(token, digit);
+        }
+        Ok(vocabulary)
+    }
+}
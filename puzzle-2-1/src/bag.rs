@@ -0,0 +1,87 @@
+use aoc_common::counter::Counter;
+use std::{collections::HashSet, fmt, str::FromStr};
+
+/// How many cubes of each color are in the bag. Colors that aren't mentioned have none
+#[derive(Debug, Clone, Default)]
+pub struct Bag {
+    counts: Counter<String>,
+}
+
+impl Bag {
+    pub fn get(&self, color: &str) -> usize {
+        self.counts.get(&color.to_string())
+    }
+
+    /// Makes sure there are at least `counts` of each color
+    pub fn fit(&mut self, counts: &Counter<String>) {
+        self.counts.merge(counts);
+    }
+
+    /// Colors and counts sorted by color, so they always print the same way
+    pub fn sorted(&self) -> Vec<(String, usize)> {
+        let mut counts: Vec<_> = self.counts.iter().collect();
+        counts.sort();
+        counts
+    }
+}
+
+impl PartialEq for Bag {
+    fn eq(&self, other: &Self) -> bool {
+        self.sorted() == other.sorted()
+    }
+}
+
+/// `red=12,green=13,blue=14`, the same way it's parsed
+impl fmt::Display for Bag {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let counts: Vec<String> = self
+            .sorted()
+            .iter()
+            .map(|(color, count)| format!("{color}={count}"))
+            .collect();
+        write!(f, "{}", counts.join(","))
+    }
+}
+
+#[derive(Debug, PartialEq, thiserror::Error)]
+pub enum ParseBagErr {
+    #[error("expected `color=count`, found {0:?}")]
+    BadEntry(String),
+    #[error("{0:?} isn't a count of cubes")]
+    BadCount(String),
+    #[error("{0} is given more than once")]
+    Repeated(String),
+}
+
+/// `color=count` pairs split by commas or new lines, as given on the command line or in a
+/// bag file. Lines starting with `#` are skipped
+impl FromStr for Bag {
+    type Err = ParseBagErr;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut bag = Bag::default();
+        let mut seen = HashSet::new();
+        let entries = s
+            .lines()
+            .filter(|line| !line.trim_start().starts_with('#'))
+            .flat_map(|line| line.split(','))
+            .map(str::trim)
+            .filter(|entry| !entry.is_empty());
+
+        for entry in entries {
+            let (color, count) = entry
+                .split_once('=')
+                .map(|(color, count)| (color.trim(), count.trim()))
+                .filter(|(color, _)| !color.is_empty())
+                .ok_or_else(|| ParseBagErr::BadEntry(entry.to_string()))?;
+            let count = count
+                .parse()
+                .map_err(|_| ParseBagErr::BadCount(count.to_string()))?;
+            if !seen.insert(color) {
+                return Err(ParseBagErr::Repeated(color.to_string()));
+            }
+            bag.counts.add_n(color.to_string(), count);
+        }
+        Ok(bag)
+    }
+}
@@ -0,0 +1,373 @@
+//! Guesses what's in the bag from the pulls, by maximum likelihood. Each pull is a handful
+//! of cubes taken from the bag without looking, so the chance of seeing it is hypergeometric.
+//! If the cubes go back in the bag between pulls, as in the puzzle, every pull is a separate
+//! handful. If they don't, a whole game is one big handful.
+
+use crate::Game;
+use aoc_common::checked::CheckedIterator;
+use std::fmt;
+
+/// Half the chi-squared 95% point with one degree of freedom. A count is inside the bounds
+/// if its log likelihood is within this of the best
+const HALF_CHI_SQUARED_95: f64 = 3.841 / 2.0;
+
+/// How much the whole bag is grown or shrunk by when looking for a better one
+const SCALES: [f64; 6] = [0.5, 0.8, 0.95, 1.05, 1.25, 2.0];
+
+#[derive(clap::ValueEnum, Debug, Clone, Copy, Default, PartialEq)]
+pub enum Sampling {
+    /// Cubes go back in the bag after each pull
+    #[default]
+    WithReplacement,
+    /// Cubes stay out until the game is over
+    WithoutReplacement,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ColorEstimate {
+    pub color: String,
+    /// Most likely number of cubes
+    pub count: usize,
+    /// 95% bounds, holding the other colors at their most likely counts. `upper` is `None`
+    /// if even the most cubes that were looked at is still inside them
+    pub lower: usize,
+    pub upper: Option<usize>,
+    /// Fraction of the bag that's this color
+    pub share: f64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Estimate {
+    pub sampling: Sampling,
+    /// Sorted by color
+    pub colors: Vec<ColorEstimate>,
+    /// Some color is at the most cubes that were looked at, and the pulls would be more
+    /// likely with more. Then the counts mean little, but the shares still do
+    pub at_limit: bool,
+    /// Game ids with how surprising the game is under the estimate, in bits, the most
+    /// surprising first
+    pub surprise: Vec<(u32, f64)>,
+}
+
+/// What was seen, as counts of each color per handful
+struct Model {
+    colors: Vec<String>,
+    /// Handfuls per game, by game id
+    games: Vec<(u32, Vec<Vec<usize>>)>,
+    /// `ln(n!)` for every `n` that's needed
+    ln_factorial: Vec<f64>,
+}
+
+impl Model {
+    fn new(games: &[Game], sampling: Sampling, max_cubes: usize) -> Self {
+        let mut colors: Vec<String> = games
+            .iter()
+            .flat_map(|game| &game.pulls)
+            .flat_map(|pull| pull.counts.iter().map(|(color, _)| color))
+            .collect();
+        colors.sort();
+        colors.dedup();
+
+        let games: Vec<(u32, Vec<Vec<usize>>)> = games
+            .iter()
+            .map(|game| {
+                let pulls = game.pulls.iter().map(|pull| {
+                    colors
+                        .iter()
+                        .map(|color| pull.counts.get(color))
+                        .collect::<Vec<usize>>()
+                });
+                let handfuls = match sampling {
+                    Sampling::WithReplacement => pulls.collect(),
+                    Sampling::WithoutReplacement => {
+                        let mut total = vec![0_usize; colors.len()];
+                        for pull in pulls {
+                            for (t, n) in total.iter_mut().zip(pull) {
+                                *t = t.checked_add(n).expect("too many cubes pulled");
+                            }
+                        }
+                        vec![total]
+                    }
+                };
+                (game.id, handfuls)
+            })
+            .collect();
+
+        let mut model = Model {
+            colors,
+            games,
+            ln_factorial: Vec::new(),
+        };
+        let most = model
+            .fewest()
+            .iter()
+            .map(|fewest| (*fewest).max(max_cubes))
+            .checked_sum()
+            .expect("too many cubes to look at");
+        model.ln_factorial = std::iter::once(0.0)
+            .chain((1..=most).scan(0.0, |ln, n| {
+                *ln += (n as f64).ln();
+                Some(*ln)
+            }))
+            .collect();
+        model
+    }
+
+    /// The fewest cubes of each color that could give every handful
+    fn fewest(&self) -> Vec<usize> {
+        let mut fewest = vec![0; self.colors.len()];
+        for handful in self.games.iter().flat_map(|(_, handfuls)| handfuls) {
+            for (fewest, count) in fewest.iter_mut().zip(handful) {
+                *fewest = (*fewest).max(*count);
+            }
+        }
+        fewest
+    }
+
+    fn ln_choose(&self, n: usize, k: usize) -> f64 {
+        self.ln_factorial[n] - self.ln_factorial[k] - self.ln_factorial[n - k]
+    }
+
+    /// Log likelihood of one game's handfuls coming out of `bag`
+    fn game_ln_likelihood(&self, handfuls: &[Vec<usize>], bag: &[usize]) -> f64 {
+        // Neither can be more than `Model::new` made room for
+        let cubes = bag.iter().copied().checked_sum().unwrap();
+        handfuls
+            .iter()
+            .map(|handful| {
+                let taken = handful.iter().copied().checked_sum().unwrap();
+                let ways: f64 = bag
+                    .iter()
+                    .zip(handful)
+                    .map(|(count, n)| self.ln_choose(*count, *n))
+                    .sum();
+                ways - self.ln_choose(cubes, taken)
+            })
+            .sum()
+    }
+
+    fn ln_likelihood(&self, bag: &[usize]) -> f64 {
+        self.games
+            .iter()
+            .map(|(_, handfuls)| self.game_ln_likelihood(handfuls, bag))
+            .sum()
+    }
+
+    /// Log likelihood for each count of `color` from `fewest` up to `most`, with the rest of
+    /// the bag as it is
+    fn scan(&self, bag: &[usize], color: usize, fewest: usize, most: usize) -> Vec<f64> {
+        let mut bag = bag.to_vec();
+        (fewest..=most)
+            .map(|count| {
+                bag[color] = count;
+                self.ln_likelihood(&bag)
+            })
+            .collect()
+    }
+}
+
+/// Estimates the bag from `games`, looking at up to `max_cubes` of each color (or as many as
+/// were seen at once, if that's more)
+pub fn estimate(games: &[Game], sampling: Sampling, max_cubes: usize) -> Estimate {
+    let model = Model::new(games, sampling, max_cubes);
+    let fewest = model.fewest();
+    let most: Vec<usize> = fewest.iter().map(|f| (*f).max(max_cubes)).collect();
+
+    // One color at a time, move to its best count until none of them move. The best bags
+    // often lie along a ridge where every color grows together, which moving one color at a
+    // time creeps along very slowly, so try scaling the whole bag too
+    let mut bag = fewest.clone();
+    loop {
+        let mut moved = false;
+        for color in 0..bag.len() {
+            let scan = model.scan(&bag, color, fewest[color], most[color]);
+            let best = fewest[color] + argmax(&scan);
+            if best != bag[color] {
+                bag[color] = best;
+                moved = true;
+            }
+        }
+
+        let scaled = SCALES.iter().map(|scale| {
+            bag.iter()
+                .zip(fewest.iter().zip(&most))
+                .map(|(count, (fewest, most))| {
+                    let count = (*count as f64 * scale).round() as usize;
+                    count.clamp(*fewest, *most)
+                })
+                .collect::<Vec<usize>>()
+        });
+        let current = model.ln_likelihood(&bag);
+        let best = scaled
+            .map(|scaled| (model.ln_likelihood(&scaled), scaled))
+            .max_by(|a, b| a.0.total_cmp(&b.0));
+        if let Some((ln, scaled)) = best {
+            if ln > current {
+                bag = scaled;
+                moved = true;
+            }
+        }
+
+        if !moved {
+            break;
+        }
+    }
+    let at_limit = bag.iter().zip(&most).any(|(count, most)| count == most);
+
+    let best = model.ln_likelihood(&bag);
+    let cubes = bag.iter().copied().checked_sum().unwrap();
+    let colors = model
+        .colors
+        .iter()
+        .enumerate()
+        .map(|(color, name)| {
+            let scan = model.scan(&bag, color, fewest[color], most[color]);
+            let inside: Vec<usize> = (fewest[color]..)
+                .zip(scan)
+                .filter(|(_, ln)| best - ln <= HALF_CHI_SQUARED_95)
+                .map(|(count, _)| count)
+                .collect();
+            let upper = inside.last().copied().filter(|upper| *upper < most[color]);
+            ColorEstimate {
+                color: name.clone(),
+                count: bag[color],
+                lower: inside.first().copied().unwrap_or(bag[color]),
+                upper,
+                share: bag[color] as f64 / cubes as f64,
+            }
+        })
+        .collect();
+
+    let mut surprise: Vec<(u32, f64)> = model
+        .games
+        .iter()
+        .map(|(id, handfuls)| {
+            let bits = -model.game_ln_likelihood(handfuls, &bag) / std::f64::consts::LN_2;
+            (*id, bits)
+        })
+        .collect();
+    surprise.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(&b.0)));
+
+    Estimate {
+        sampling,
+        colors,
+        at_limit,
+        surprise,
+    }
+}
+
+/// Index of the largest value, the first if there's a tie
+fn argmax(values: &[f64]) -> usize {
+    let mut best = 0;
+    for (idx, value) in values.iter().enumerate() {
+        if *value > values[best] {
+            best = idx;
+        }
+    }
+    best
+}
+
+impl fmt::Display for Estimate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sampling = match self.sampling {
+            Sampling::WithReplacement => "with replacement",
+            Sampling::WithoutReplacement => "without replacement",
+        };
+        writeln!(f, "Most likely bag, pulling {sampling}, with 95% bounds:")?;
+        for color in &self.colors {
+            let upper = color
+                .upper
+                .map_or("?".to_string(), |upper| upper.to_string());
+            let bounds = format!("[{}, {upper}]", color.lower);
+            writeln!(
+                f,
+                "  {:<8} {:>4}  {bounds:<12} {:>5.1}%",
+                color.color,
+                color.count,
+                color.share * 100.0
+            )?;
+        }
+        if self.at_limit {
+            writeln!(
+                f,
+                "The pulls look like they came from an even bigger bag, only the shares can be \
+                 trusted. Try a larger --max-cubes"
+            )?;
+        }
+        writeln!(f, "Surprise of each game, in bits:")?;
+        for (id, bits) in &self.surprise {
+            writeln!(f, "  Game {id:<4} {bits:>7.2}")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn games(lines: &[&str]) -> Vec<Game> {
+        lines.iter().map(|line| line.parse().unwrap()).collect()
+    }
+
+    #[test]
+    fn emptied_bag() {
+        // Without replacement this game took every cube, so the bag is known exactly. More
+        // red cubes would make seeing only 2 of them less likely, past 5 too unlikely
+        let games = games(&["Game 1: 2 red; 3 blue"]);
+        let found = estimate(&games, Sampling::WithoutReplacement, 50);
+        assert_eq!(
+            found.colors,
+            vec![
+                ColorEstimate {
+                    color: "blue".to_string(),
+                    count: 3,
+                    lower: 3,
+                    upper: Some(10),
+                    share: 0.6,
+                },
+                ColorEstimate {
+                    color: "red".to_string(),
+                    count: 2,
+                    lower: 2,
+                    upper: Some(5),
+                    share: 0.4,
+                },
+            ]
+        );
+        assert!(!found.at_limit);
+        assert_eq!(found.surprise, vec![(1, 0.0)]);
+    }
+
+    #[test]
+    fn surprising_games() {
+        let games = games(&[
+            "Game 1: 1 red, 1 blue; 1 red, 1 blue; 1 blue, 1 red",
+            "Game 2: 1 blue, 1 red; 1 red, 1 blue; 1 red, 1 blue",
+            "Game 3: 2 red; 1 red, 1 blue; 2 blue",
+        ]);
+        let found = estimate(&games, Sampling::WithReplacement, 30);
+        // Nearly always one of each, so there can't be many cubes of either color
+        let counts: Vec<usize> = found.colors.iter().map(|c| c.count).collect();
+        assert!(
+            counts.iter().all(|count| (2..10).contains(count)),
+            "{counts:?}"
+        );
+        assert_eq!(counts[0], counts[1]);
+        assert_eq!(found.surprise[0].0, 3);
+        assert!(found.surprise[0].1 > found.surprise[1].1 + 1.0);
+
+        // The same handfuls are far less surprising when cubes stay out. Every game took the
+        // same cubes in all, so none of them stands out any more
+        let kept = estimate(&games, Sampling::WithoutReplacement, 30);
+        for (id, bits) in &found.surprise {
+            let (_, kept_bits) = kept
+                .surprise
+                .iter()
+                .find(|(kept_id, _)| kept_id == id)
+                .unwrap();
+            assert!(kept_bits + 1.0 < *bits, "game {id}: {kept_bits} vs {bits}");
+        }
+        assert_eq!(kept.surprise[0].1, kept.surprise[2].1);
+    }
+}
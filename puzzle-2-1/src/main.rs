@@ -1,4 +1,10 @@
+mod bag;
+mod estimate;
+
+use aoc_common::{checked::CheckedIterator, counter::Counter};
+use bag::Bag;
 use clap::Parser;
+use estimate::Sampling;
 use std::{
     fs::File,
     io::{self, BufRead},
@@ -10,40 +16,105 @@ use std::{
 #[command(author, version, about, long_about = None)]
 struct Cli {
     input: PathBuf,
+    /// Cubes in the bag, as `color=count` pairs
+    #[arg(long, default_value = "red=12,green=13,blue=14")]
+    bag: Bag,
+    /// File of `color=count` pairs to use as the bag, split by commas or new lines
+    #[arg(long, conflicts_with = "bag")]
+    bag_file: Option<PathBuf>,
+    /// Also print the smallest bag that could have played these games, like `1,3,5`
+    #[arg(long, value_delimiter = ',')]
+    smallest: Vec<u32>,
+    /// Estimate what's in the bag from the pulls, and how surprising each game is
+    #[arg(long)]
+    estimate: bool,
+    /// Whether cubes go back in the bag between pulls of a game, for `--estimate`
+    #[arg(long, value_enum, default_value_t, requires = "estimate")]
+    sampling: Sampling,
+    /// Most cubes of each color to consider, for `--estimate`
+    #[arg(long, default_value_t = 100, requires = "estimate")]
+    max_cubes: usize,
 }
 
 fn main() {
-    let max_red: Pulls = 12;
-    let max_blue: Pulls = 14;
-    let max_green: Pulls = 13;
-
-    let input_filename = Cli::parse().input;
+    let cli = Cli::parse();
+    let bag: Bag = match &cli.bag_file {
+        Some(path) => std::fs::read_to_string(path).unwrap().parse().unwrap(),
+        None => cli.bag,
+    };
 
     let mut games: Vec<Game> = Vec::new();
-    for line in read_lines(input_filename).unwrap() {
+    for line in read_lines(cli.input).unwrap() {
         games.push(line.unwrap().parse().unwrap())
     }
 
-    let sum: u32 = games
+    if cli.estimate {
+        print!(
+            "{}",
+            estimate::estimate(&games, cli.sampling, cli.max_cubes)
+        );
+        return;
+    }
+
+    let (feasible, infeasible): (Vec<&Game>, Vec<&Game>) = games
         .iter()
-        .filter(|game| {
-            !game
-                .pulls
+        .partition(|game| game.breaking_pull(&bag).is_none());
+    let ids: Vec<String> = feasible.iter().map(|game| game.id.to_string()).collect();
+    println!("Feasible with {bag}: {}", ids.join(", "));
+    for game in infeasible {
+        let (idx, color, count) = game.breaking_pull(&bag).unwrap();
+        println!(
+            "Game {} is impossible, pull {} has {count} {color} but the bag has {}",
+            game.id,
+            idx + 1,
+            bag.get(&color)
+        );
+    }
+
+    if !cli.smallest.is_empty() {
+        let chosen = cli.smallest.iter().map(|id| {
+            games
                 .iter()
-                .any(|pull| pull.red > max_red || pull.blue > max_blue || pull.green > max_green)
-        })
+                .find(|game| game.id == *id)
+                .unwrap_or_else(|| panic!("there's no game {id}"))
+        });
+        println!("Smallest bag: {}", smallest_bag(chosen));
+    }
+
+    let sum: u32 = feasible
+        .iter()
         .map(|game| game.id)
-        .sum();
+        .checked_sum()
+        .expect("answer is too big");
 
     println!("Final: {sum}");
 }
 
-type Pulls = u32;
+/// The fewest cubes of each color that all the `games` could have been played with
+fn smallest_bag<'a>(games: impl IntoIterator<Item = &'a Game>) -> Bag {
+    let mut bag = Bag::default();
+    for pull in games.into_iter().flat_map(|game| game.pulls.iter()) {
+        bag.fit(&pull.counts);
+    }
+    bag
+}
+
+/// How many cubes of each color were pulled, colors can be anything
 #[derive(Default, Debug)]
 struct Pull {
-    red: Pulls,
-    blue: Pulls,
-    green: Pulls,
+    counts: Counter<String>,
+}
+
+impl Pull {
+    /// A color there are more of than in the bag, with how many were pulled. If there's more
+    /// than one the first alphabetically is picked
+    fn excess(&self, bag: &Bag) -> Option<(String, usize)> {
+        let mut counts: Vec<(String, usize)> = self.counts.iter().collect();
+        counts.sort();
+        counts
+            .into_iter()
+            .find(|(color, count)| *count > bag.get(color))
+    }
 }
 
 #[derive(Debug)]
@@ -52,6 +123,17 @@ struct Game {
     pulls: Vec<Pull>,
 }
 
+impl Game {
+    /// The first pull that couldn't have come from `bag`, by its index, with the color that
+    /// broke it and how many of that color were pulled
+    fn breaking_pull(&self, bag: &Bag) -> Option<(usize, String, usize)> {
+        self.pulls
+            .iter()
+            .enumerate()
+            .find_map(|(idx, pull)| pull.excess(bag).map(|(color, count)| (idx, color, count)))
+    }
+}
+
 #[derive(Debug)]
 struct ParsePullErr;
 
@@ -59,24 +141,16 @@ impl FromStr for Pull {
     type Err = ParsePullErr;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut red: Pulls = 0;
-        let mut blue: Pulls = 0;
-        let mut green: Pulls = 0;
+        let mut counts = Counter::new();
         let s = s.trim();
 
         for count_color in s.split(", ") {
-            let mut a = count_color.split(' ');
-            let count: Pulls = a.next().unwrap().parse().unwrap();
-            let color = a.next().unwrap();
-            match color {
-                "red" => red = count,
-                "blue" => blue = count,
-                "green" => green = count,
-                _ => return Err(ParsePullErr),
-            }
+            let (count, color) = count_color.split_once(' ').ok_or(ParsePullErr)?;
+            let count = count.parse().map_err(|_| ParsePullErr)?;
+            counts.add_n(color.to_string(), count);
         }
 
-        Ok(Pull { red, blue, green })
+        Ok(Pull { counts })
     }
 }
 
@@ -113,3 +187,68 @@ where
     let file = File::open(filename)?;
     Ok(io::BufReader::new(file).lines())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn any_colors() {
+        let game: Game = "Game 7: 3 yellow, 1 red; 2 purple, 4 yellow"
+            .parse()
+            .unwrap();
+        let bag: Bag = "red=1, yellow=4\npurple=2".parse().unwrap();
+        assert_eq!(game.breaking_pull(&bag), None);
+
+        let small: Bag = "# No purple\nred=1,yellow=4".parse().unwrap();
+        assert_eq!(
+            game.breaking_pull(&small),
+            Some((1, "purple".to_string(), 2))
+        );
+        assert!("3 red, blue".parse::<Pull>().is_err());
+    }
+
+    #[test]
+    fn bags() {
+        let games: Vec<Game> = [
+            "Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green",
+            "Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue",
+            "Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red",
+        ]
+        .iter()
+        .map(|line| line.parse().unwrap())
+        .collect();
+
+        let bag: Bag = "red=12,green=13,blue=14".parse().unwrap();
+        assert_eq!(
+            games[2].breaking_pull(&bag),
+            Some((0, "red".to_string(), 20))
+        );
+        assert_eq!(
+            smallest_bag(&games[..2]).to_string(),
+            "blue=6,green=3,red=4"
+        );
+        assert_eq!(
+            smallest_bag(&games),
+            "red=20,green=13,blue=6".parse().unwrap()
+        );
+
+        use bag::ParseBagErr;
+        assert_eq!(
+            "red=12,green".parse::<Bag>(),
+            Err(ParseBagErr::BadEntry("green".to_string()))
+        );
+        assert_eq!(
+            "red=-1".parse::<Bag>(),
+            Err(ParseBagErr::BadCount("-1".to_string()))
+        );
+        assert_eq!(
+            "red=1,red=2".parse::<Bag>(),
+            Err(ParseBagErr::Repeated("red".to_string()))
+        );
+        assert_eq!(
+            "red=0,blue=1,red=5".parse::<Bag>(),
+            Err(ParseBagErr::Repeated("red".to_string()))
+        );
+    }
+}
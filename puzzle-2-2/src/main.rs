@@ -1,5 +1,7 @@
+use aoc_common::{checked::CheckedIterator, counter::Counter};
 use clap::Parser;
 use std::{
+    collections::HashSet,
     fs::File,
     io::{self, BufRead},
     path::{Path, PathBuf},
@@ -20,36 +22,46 @@ fn main() {
         games.push(line.unwrap().parse().unwrap())
     }
 
-    let sum: u32 = games
+    let sum = total_power(&games);
+    println!("Final: {sum}");
+}
+
+/// Sum of each game's power, the product of the fewest cubes of each color it could be
+/// played with. A color seen in any game counts for all of them, so a game that never pulls
+/// it has a power of 0
+fn total_power(games: &[Game]) -> usize {
+    let colors: HashSet<String> = games
+        .iter()
+        .flat_map(|game| game.pulls.iter())
+        .flat_map(|pull| pull.counts.iter().map(|(color, _)| color))
+        .collect();
+
+    games
         .iter()
         .map(|game| {
-            let max_red = game.pulls.iter().max_by_key(|pull| pull.red).unwrap().red;
-            let max_blue = game.pulls.iter().max_by_key(|pull| pull.blue).unwrap().blue;
-            let max_green = game
-                .pulls
+            let mut maxes = Counter::new();
+            for pull in game.pulls.iter() {
+                maxes.merge(&pull.counts);
+            }
+            colors
                 .iter()
-                .max_by_key(|pull| pull.green)
-                .unwrap()
-                .green;
-            (max_red, max_blue, max_green)
+                .map(|color| maxes.get(color))
+                .checked_product()
+                .expect("power is too big")
         })
-        .map(|maxes| maxes.0 * maxes.1 * maxes.2)
-        .sum();
-
-    println!("Final: {sum}");
+        .checked_sum()
+        .expect("answer is too big")
 }
 
-type Pulls = u32;
+/// How many cubes of each color were pulled, colors can be anything
 #[derive(Default, Debug)]
 struct Pull {
-    red: Pulls,
-    blue: Pulls,
-    green: Pulls,
+    counts: Counter<String>,
 }
 
 #[derive(Debug)]
 struct Game {
-    id: u32,
+    _id: u32,
     pulls: Vec<Pull>,
 }
 
@@ -60,24 +72,16 @@ impl FromStr for Pull {
     type Err = ParsePullErr;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut red: Pulls = 0;
-        let mut blue: Pulls = 0;
-        let mut green: Pulls = 0;
+        let mut counts = Counter::new();
         let s = s.trim();
 
         for count_color in s.split(", ") {
-            let mut a = count_color.split(' ');
-            let count: Pulls = a.next().unwrap().parse().unwrap();
-            let color = a.next().unwrap();
-            match color {
-                "red" => red = count,
-                "blue" => blue = count,
-                "green" => green = count,
-                _ => return Err(ParsePullErr),
-            }
+            let (count, color) = count_color.split_once(' ').ok_or(ParsePullErr)?;
+            let count = count.parse().map_err(|_| ParsePullErr)?;
+            counts.add_n(color.to_string(), count);
         }
 
-        Ok(Pull { red, blue, green })
+        Ok(Pull { counts })
     }
 }
 
@@ -101,7 +105,7 @@ impl FromStr for Game {
             pulls.push(pull_str.parse().unwrap());
         }
 
-        Ok(Game { id, pulls })
+        Ok(Game { _id: id, pulls })
     }
 }
 
@@ -114,3 +118,26 @@ where
     let file = File::open(filename)?;
     Ok(io::BufReader::new(file).lines())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn any_colors() {
+        let mut games: Vec<Game> = [
+            "Game 1: 3 yellow, 1 purple; 2 purple, 4 yellow",
+            "Game 2: 5 purple, 1 yellow; 2 purple",
+        ]
+        .iter()
+        .map(|line| line.parse().unwrap())
+        .collect();
+        // Purple then yellow
+        assert_eq!(total_power(&games), 2 * 4 + 5);
+
+        // Nobody else pulled red
+        let red: Game = "Game 3: 1 red, 6 yellow".parse().unwrap();
+        games.push(red);
+        assert_eq!(total_power(&games), 0);
+    }
+}
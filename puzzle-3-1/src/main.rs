@@ -1,5 +1,5 @@
+use aoc_common::{checked::CheckedIterator, cursor::Cursor};
 use clap::Parser;
-use regex::Regex;
 use std::{
     collections::HashMap,
     ops::{Add, Sub},
@@ -33,7 +33,8 @@ fn run_puzzle(input: &str) -> u32 {
         .iter()
         .filter(|n| n.is_part_number(&schematic))
         .map(|n| n.value)
-        .sum()
+        .checked_sum()
+        .expect("answer is too big")
 }
 
 #[derive(Debug)]
@@ -96,8 +97,9 @@ impl Number {
 
 #[derive(Debug, Default)]
 enum Item {
-    Symbol(char),
-    Number(Rc<Number>),
+    // Part 1 only asks where the symbols and numbers are, these show up in `Debug`
+    Symbol(#[allow(dead_code)] char),
+    Number(#[allow(dead_code)] Rc<Number>),
     #[default]
     Other,
 }
@@ -183,26 +185,25 @@ fn schematic_parse_line(
     items: &mut HashMap<Point, Item>,
 ) -> Vec<Rc<Number>> {
     let mut numbers = Vec::new();
-    // This matches a group of periods OR a group of digits OR a group of symbols
-    let re = Regex::new(r"[0-9]+|[^.^0-9]+|[.]+").unwrap();
+    let mut cursor = Cursor::new(line.as_bytes());
 
-    let mut idx = 0;
-    for match_str in re.find_iter(line) {
+    while let Some(byte) = cursor.peek() {
         let location = Point {
             row: line_index,
-            col: idx,
+            col: cursor.offset(),
         };
 
-        let current_char = match_str.as_str().chars().next().unwrap();
-        match current_char {
-            '.' => (),
-            '0'..='9' => {
-                let value = match_str
-                    .as_str()
-                    .parse()
-                    .expect("Could not parse number in schematic");
+        match byte {
+            b'.' => cursor.skip_while(|byte| byte == b'.'),
+            b'0'..=b'9' => {
+                let value = cursor.next_uint().unwrap_or_else(|err| {
+                    panic!(
+                        "Could not parse number in schematic on line {}: {err}",
+                        line_index + 1
+                    )
+                });
 
-                let length = match_str.len();
+                let length = cursor.offset() - location.col;
 
                 let new_number = Rc::new(Number {
                     value,
@@ -216,11 +217,11 @@ fn schematic_parse_line(
                 numbers.push(new_number)
             }
             _ => {
-                // Should be a single char symbol (#, $, _, etc.)
-                items.insert(location, Item::Symbol(current_char));
+                // A single char symbol (#, $, _, etc.), assumes the schematic is ASCII
+                cursor.next_byte();
+                items.insert(location, Item::Symbol(byte as char));
             }
         }
-        idx += match_str.len();
     }
     numbers
 }
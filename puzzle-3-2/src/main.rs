@@ -1,5 +1,5 @@
+use aoc_common::{checked::CheckedIterator, cursor::Cursor};
 use clap::Parser;
-use regex::Regex;
 use std::{
     collections::HashMap,
     ops::{Add, Sub},
@@ -32,7 +32,8 @@ fn run_puzzle(input: &str) -> u32 {
         .items
         .iter()
         .map(|item| item.1.gear_ratio(*item.0, &schematic).unwrap_or(0))
-        .sum()
+        .checked_sum()
+        .expect("answer is too big")
 }
 
 #[derive(Debug, PartialEq)]
@@ -73,7 +74,8 @@ impl Item {
             }
 
             if unique_numbers.len() == 2 {
-                Some(unique_numbers[0].value * unique_numbers[1].value)
+                let ratio = unique_numbers.iter().map(|number| number.value);
+                Some(ratio.checked_product().expect("gear ratio is too big"))
             } else {
                 None
             }
@@ -207,26 +209,25 @@ fn schematic_parse_line(
     items: &mut HashMap<Point, Item>,
 ) -> Vec<Rc<Number>> {
     let mut numbers = Vec::new();
-    // This matches a group of periods OR a group of digits OR a group of symbols
-    let re = Regex::new(r"[0-9]+|[^.^0-9]+|[.]+").unwrap();
+    let mut cursor = Cursor::new(line.as_bytes());
 
-    let mut idx = 0;
-    for match_str in re.find_iter(line) {
+    while let Some(byte) = cursor.peek() {
         let location = Point {
             row: line_index,
-            col: idx,
+            col: cursor.offset(),
         };
 
-        let current_char = match_str.as_str().chars().next().unwrap();
-        match current_char {
-            '.' => (),
-            '0'..='9' => {
-                let value = match_str
-                    .as_str()
-                    .parse()
-                    .expect("Could not parse number in schematic");
+        match byte {
+            b'.' => cursor.skip_while(|byte| byte == b'.'),
+            b'0'..=b'9' => {
+                let value = cursor.next_uint().unwrap_or_else(|err| {
+                    panic!(
+                        "Could not parse number in schematic on line {}: {err}",
+                        line_index + 1
+                    )
+                });
 
-                let length = match_str.len();
+                let length = cursor.offset() - location.col;
 
                 let new_number = Rc::new(Number {
                     value,
@@ -240,11 +241,11 @@ fn schematic_parse_line(
                 numbers.push(new_number)
             }
             _ => {
-                // Should be a single char symbol (#, $, _, etc.)
-                items.insert(location, Item::Symbol(current_char));
+                // A single char symbol (#, $, _, etc.), assumes the schematic is ASCII
+                cursor.next_byte();
+                items.insert(location, Item::Symbol(byte as char));
             }
         }
-        idx += match_str.len();
     }
     numbers
 }
@@ -1,3 +1,4 @@
+use aoc_common::{bitset::BitSet, checked::CheckedIterator};
 use clap::Parser;
 use std::{path::PathBuf, str::FromStr};
 
@@ -22,24 +23,24 @@ fn run_puzzle(input: &str) -> u32 {
     input
         .lines()
         .map(|line| Game::from_str(line).unwrap().score())
-        .sum()
+        .checked_sum()
+        .expect("answer is too big")
 }
 
 struct Game {
     _id: u32,
-    winning: Vec<u8>,
-    picks: Vec<u8>,
+    winning: BitSet,
+    picks: BitSet,
 }
 
 impl Game {
     fn score(&self) -> u32 {
-        self.picks
-            .iter()
-            .filter(|pick| self.is_winning(**pick))
-            .fold(0, |acc, _| if acc == 0 { 1 } else { acc * 2 })
-    }
-    fn is_winning(&self, pick: u8) -> bool {
-        self.winning.contains(&pick)
+        match self.winning.intersection_count(&self.picks) {
+            0 => 0,
+            matches => 1_u32
+                .checked_shl(matches as u32 - 1)
+                .expect("score is too big"),
+        }
     }
 }
 
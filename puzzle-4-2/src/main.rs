@@ -1,5 +1,6 @@
+use aoc_common::{bitset::BitSet, checked::CheckedIterator, memo::Memo};
 use clap::Parser;
-use std::{collections::HashMap, path::PathBuf, str::FromStr};
+use std::{path::PathBuf, str::FromStr};
 
 #[derive(Parser)]
 #[command(author, version, about, long_about = None)]
@@ -25,27 +26,13 @@ fn run_puzzle(input: &str) -> u32 {
 #[derive(Debug)]
 struct Game {
     id: usize,
-    winning: Vec<u8>,
-    picks: Vec<u8>,
+    winning: BitSet,
+    picks: BitSet,
 }
 
 impl Game {
-    fn score(&self, totals: &mut HashMap<usize, u32>) {
-        let points = self
-            .picks
-            .iter()
-            .filter(|pick| self.is_winning(**pick))
-            .count();
-
-        for idx in 1..=points {
-            let mut value = *totals.get(&(idx + self.id)).unwrap();
-            value += totals[&self.id];
-            totals.insert(idx + self.id, value);
-        }
-    }
-
-    fn is_winning(&self, pick: u8) -> bool {
-        self.winning.contains(&pick)
+    fn matches(&self) -> usize {
+        self.winning.intersection_count(&self.picks)
     }
 }
 
@@ -79,13 +66,20 @@ struct GamesList {
 
 impl GamesList {
     fn score(&self) -> u32 {
-        let mut sums = HashMap::from_iter((1..=self.games.len()).map(|idx| (idx, 1)));
-        for game in self.games.iter() {
-            println!("Current head {}", game.id);
-
-            game.score(&mut sums)
-        }
-        sums.into_values().sum()
+        // A card counts itself plus every card its matches win, and every card those win...
+        let mut cards = Memo::new(|cards: &mut dyn FnMut(usize) -> u32, id: usize| {
+            let game = &self.games[id - 1];
+            let won = (game.id + 1..=game.id + game.matches()).map(&mut *cards);
+            std::iter::once(1)
+                .chain(won)
+                .checked_sum()
+                .expect("too many copies")
+        });
+        self.games
+            .iter()
+            .map(|game| cards.get(game.id))
+            .checked_sum()
+            .expect("answer is too big")
     }
 }
 
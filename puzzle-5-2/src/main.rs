@@ -1,28 +1,53 @@
 use clap::Parser;
 use itertools::Itertools;
 use rayon::prelude::*;
-use std::{ops::Range, path::PathBuf, str::FromStr};
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::Display,
+    ops::Range,
+    path::PathBuf,
+    str::FromStr,
+};
 
 #[derive(Parser)]
 #[command(author, version, about, long_about = None)]
 struct Cli {
     input: PathBuf,
+    /// How to handle map entries with overlapping source ranges
+    #[arg(long, value_enum, default_value_t)]
+    overlaps: OverlapPolicy,
+    /// Print every overlap, gap and empty entry in the almanac's maps
+    #[arg(long)]
+    report: bool,
 }
 
 fn main() {
-    let input_filename = Cli::parse().input;
-    let result = run_puzzle_file(&input_filename);
+    let cli = Cli::parse();
+    match solve(&cli) {
+        Ok(result) => println!("Final: {result}"),
+        Err(err) => {
+            eprintln!("{err}");
+            std::process::exit(1);
+        }
+    }
+}
 
-    println!("Final: {result}");
+/// Prints the report first if it was asked for, a bad almanac fails either way
+fn solve(cli: &Cli) -> Result<i64, AlmanacErr> {
+    if cli.report {
+        print_report(&cli.input)?;
+    }
+    run_puzzle_file(&cli.input, cli.overlaps)
 }
 
-fn run_puzzle_file(filename: &PathBuf) -> i64 {
-    run_puzzle(&std::fs::read_to_string(filename).unwrap())
+fn run_puzzle_file(filename: &PathBuf, overlaps: OverlapPolicy) -> Result<i64, AlmanacErr> {
+    run_puzzle(&std::fs::read_to_string(filename).unwrap(), overlaps)
 }
 
-fn run_puzzle(input: &str) -> i64 {
-    let (seed_line, other_lines) = input.split_once('\n').unwrap();
-    let almanac = Almanac::from_str(other_lines.trim()).unwrap();
+fn run_puzzle(input: &str, overlaps: OverlapPolicy) -> Result<i64, AlmanacErr> {
+    let almanac = Almanac::parse(input, overlaps)?;
+    let maps = almanac.path("seed", "location")?;
+    let (seed_line, _) = input.split_once('\n').unwrap();
 
     let (_, seed_line) = seed_line.split_once(' ').unwrap();
     let a: Vec<i64> = seed_line
@@ -33,14 +58,38 @@ fn run_puzzle(input: &str) -> i64 {
     let b = a
         .par_chunks(2)
         .flat_map(|ac| ac[0]..(ac[0] + ac[1]))
-        .map(|s| almanac.map_to_location(s))
+        .map(|s| maps.iter().fold(s, |v, map| map.map(v)))
         .min()
         .unwrap();
-    b
+    Ok(b)
+}
+
+fn print_report(filename: &PathBuf) -> Result<(), AlmanacErr> {
+    print!("{}", report(&std::fs::read_to_string(filename).unwrap())?);
+    Ok(())
+}
+
+/// Every overlap, gap and empty entry in the almanac, under the map it's in
+fn report(input: &str) -> Result<String, AlmanacErr> {
+    // Overlaps are only reported, so parse with a policy that allows them
+    let almanac = Almanac::parse(input, OverlapPolicy::FirstWins)?;
+
+    let mut report = String::new();
+    for map in almanac.maps.iter() {
+        let issues = map.validate();
+        if !issues.is_empty() {
+            report += &format!("{}-to-{} map:\n", map.source, map.destination);
+            for issue in issues {
+                report += &format!("  {issue}\n");
+            }
+        }
+    }
+    Ok(report)
 }
 
 #[derive(Debug)]
 struct MapEntry {
+    line: usize,
     source_range: Range<i64>,
     _dest_range: Range<i64>,
     difference: i64,
@@ -56,23 +105,44 @@ impl MapEntry {
     }
 }
 
-#[derive(Debug)]
-struct ParseStrErr;
-impl FromStr for MapEntry {
-    type Err = ParseStrErr;
+#[derive(Debug, PartialEq, thiserror::Error)]
+enum AlmanacErr {
+    #[error("invalid map header: {0:?}")]
+    BadHeader(String),
+    #[error("invalid map entry on line {0}: {1:?}")]
+    BadEntry(usize, String),
+    #[error("almanac has no maps")]
+    Empty,
+    #[error("more than one map from category {0:?}")]
+    DuplicateSource(String),
+    #[error("chain of maps is broken, it starts at each of {0:?}")]
+    BrokenChain(Vec<String>),
+    #[error("chain of maps has a cycle through category {0:?}")]
+    Cycle(String),
+    #[error("category {0:?} cannot be converted to {1:?}")]
+    NotConnected(String, String),
+    #[error("{0}-to-{1} map has overlapping entries: {}", .2.iter().join(", "))]
+    Overlaps(String, String, Vec<Issue>),
+}
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
+impl MapEntry {
+    fn parse(line: usize, s: &str) -> Result<Self, AlmanacErr> {
+        let bad_entry = || AlmanacErr::BadEntry(line, s.to_owned());
         let (dest_start, source_start, length) = s
             .split_whitespace()
-            .map(|ns| ns.parse().unwrap())
+            .map(|ns| ns.parse::<i64>())
             .collect_tuple()
-            .unwrap();
+            .and_then(|(d, s, l)| Some((d.ok()?, s.ok()?, l.ok()?)))
+            .filter(|(_, _, length)| *length >= 0)
+            .ok_or_else(bad_entry)?;
 
-        let source_range = source_start..(source_start + length);
-        let dest_range = dest_start..(dest_start + length);
-        let difference = dest_start - source_start;
+        // Every value in the ranges and every mapped value has to fit in an i64
+        let source_range = source_start..source_start.checked_add(length).ok_or_else(bad_entry)?;
+        let dest_range = dest_start..dest_start.checked_add(length).ok_or_else(bad_entry)?;
+        let difference = dest_start.checked_sub(source_start).ok_or_else(bad_entry)?;
 
         Ok(MapEntry {
+            line,
             source_range,
             _dest_range: dest_range,
             difference,
@@ -80,8 +150,58 @@ impl FromStr for MapEntry {
     }
 }
 
+/// Which entry maps a value covered by more than one entry of a map
+#[derive(Debug, Clone, Copy, Default, PartialEq, clap::ValueEnum)]
+enum OverlapPolicy {
+    /// Reject the almanac
+    #[default]
+    Error,
+    /// Use the entry that comes first in the map
+    FirstWins,
+    /// Use the entry that comes last in the map
+    LastWins,
+}
+
+/// Something suspicious about a map, entries are identified by line number
+#[derive(Debug, PartialEq)]
+enum Issue {
+    /// Both entries contain the source values in `range`
+    Overlap {
+        lines: (usize, usize),
+        range: Range<i64>,
+    },
+    /// No entry between the two contains the source values in `range`
+    Gap {
+        lines: (usize, usize),
+        range: Range<i64>,
+    },
+    /// The entry has a length of zero so maps nothing
+    ZeroLength { line: usize },
+}
+
+impl Display for Issue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Issue::Overlap { lines, range } => write!(
+                f,
+                "lines {} and {} overlap on {}..{}",
+                lines.0, lines.1, range.start, range.end
+            ),
+            Issue::Gap { lines, range } => write!(
+                f,
+                "lines {} and {} leave a gap on {}..{}",
+                lines.0, lines.1, range.start, range.end
+            ),
+            Issue::ZeroLength { line } => write!(f, "line {line} has zero length"),
+        }
+    }
+}
+
 #[derive(Debug)]
 struct Map {
+    source: String,
+    destination: String,
+    /// Entries in the order they are checked, so the first match wins
     entries: Vec<MapEntry>,
 }
 
@@ -89,44 +209,193 @@ impl Map {
     fn map(&self, source_value: i64) -> i64 {
         self.entries
             .iter()
-            .map(|e| e.map(source_value))
-            .filter(|v| v.is_some())
-            .at_most_one() // There should 0-1 matching ranges
-            .unwrap()
-            .unwrap_or(Some(source_value)) // If no matching range, use source value
-            .unwrap()
+            .find_map(|e| e.map(source_value))
+            .unwrap_or(source_value) // If no matching range, use source value
     }
-}
 
-impl FromStr for Map {
-    type Err = ParseStrErr;
+    /// Parses a map from its lines, each paired with its line number
+    fn parse(lines: &[(usize, &str)], overlaps: OverlapPolicy) -> Result<Self, AlmanacErr> {
+        let (_, header) = lines[0];
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let entries = s
-            .lines()
-            .skip(1)
-            .map(|line| line.parse().unwrap())
+        // Header looks like "seed-to-soil map:"
+        let (source, destination) = header
+            .strip_suffix(" map:")
+            .and_then(|names| names.split_once("-to-"))
+            .ok_or_else(|| AlmanacErr::BadHeader(header.to_owned()))?;
+
+        let mut entries: Vec<MapEntry> = lines[1..]
+            .iter()
+            .map(|(line, s)| MapEntry::parse(*line, s))
+            .try_collect()?;
+        if overlaps == OverlapPolicy::LastWins {
+            entries.reverse();
+        }
+
+        let map = Map {
+            source: source.to_owned(),
+            destination: destination.to_owned(),
+            entries,
+        };
+
+        if overlaps == OverlapPolicy::Error {
+            let found: Vec<Issue> = map
+                .validate()
+                .into_iter()
+                .filter(|issue| matches!(issue, Issue::Overlap { .. }))
+                .collect();
+            if !found.is_empty() {
+                return Err(AlmanacErr::Overlaps(map.source, map.destination, found));
+            }
+        }
+
+        Ok(map)
+    }
+
+    /// Returns every overlap, gap, and zero length entry in the map
+    fn validate(&self) -> Vec<Issue> {
+        let mut issues = Vec::new();
+        let entries: Vec<&MapEntry> = self
+            .entries
+            .iter()
+            .sorted_by_key(|e| (e.source_range.start, e.line))
+            .collect();
+
+        for entry in entries.iter().filter(|e| e.source_range.is_empty()) {
+            issues.push(Issue::ZeroLength { line: entry.line });
+        }
+
+        let entries: Vec<&MapEntry> = entries
+            .into_iter()
+            .filter(|e| !e.source_range.is_empty())
             .collect();
-        Ok(Map { entries })
+
+        for (idx, first) in entries.iter().enumerate() {
+            for second in entries[idx + 1..].iter() {
+                if second.source_range.start >= first.source_range.end {
+                    // Sorted by start, so no later entry can overlap either
+                    break;
+                }
+                let end = first.source_range.end.min(second.source_range.end);
+                issues.push(Issue::Overlap {
+                    lines: (first.line.min(second.line), first.line.max(second.line)),
+                    range: second.source_range.start..end,
+                });
+            }
+        }
+
+        // Track the entry reaching furthest so far, anything past it is a gap
+        if let Some((first, rest)) = entries.split_first() {
+            let mut reach = *first;
+            for entry in rest {
+                if entry.source_range.start > reach.source_range.end {
+                    issues.push(Issue::Gap {
+                        lines: (reach.line, entry.line),
+                        range: reach.source_range.end..entry.source_range.start,
+                    });
+                }
+                if entry.source_range.end > reach.source_range.end {
+                    reach = entry;
+                }
+            }
+        }
+
+        issues
     }
 }
 
+/// Maps ordered so that each map's destination is the next map's source
 struct Almanac {
     maps: Vec<Map>,
 }
 
 impl FromStr for Almanac {
-    type Err = ParseStrErr;
+    type Err = AlmanacErr;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let maps = s.split("\n\n").map(|s| s.parse().unwrap()).collect();
-        Ok(Almanac { maps })
+        Almanac::parse(s, OverlapPolicy::default())
     }
 }
 
 impl Almanac {
-    fn map_to_location(&self, seed: i64) -> i64 {
-        self.maps.iter().fold(seed, |s, map| map.map(s))
+    /// Parses the maps in an almanac, the seeds line is skipped if there is one
+    fn parse(s: &str, overlaps: OverlapPolicy) -> Result<Self, AlmanacErr> {
+        // Line numbers are kept so issues can point at the input
+        let lines: Vec<(usize, &str)> = s
+            .lines()
+            .enumerate()
+            .map(|(idx, line)| (idx + 1, line))
+            .collect();
+        let blocks = lines
+            .split(|(_, line)| line.trim().is_empty())
+            .filter(|block| !block.is_empty())
+            .filter(|block| !block[0].1.starts_with("seeds:"));
+
+        let mut by_source = HashMap::new();
+        for block in blocks {
+            let map = Map::parse(block, overlaps)?;
+            if by_source.contains_key(&map.source) {
+                return Err(AlmanacErr::DuplicateSource(map.source));
+            }
+            by_source.insert(map.source.clone(), map);
+        }
+
+        if by_source.is_empty() {
+            return Err(AlmanacErr::Empty);
+        }
+
+        // The chain starts at the only category that nothing maps to
+        let destinations: HashSet<&String> = by_source.values().map(|m| &m.destination).collect();
+        let starts: Vec<String> = by_source
+            .keys()
+            .filter(|source| !destinations.contains(source))
+            .cloned()
+            .sorted()
+            .collect();
+
+        let mut category = match starts.as_slice() {
+            [start] => start.clone(),
+            [] => return Err(AlmanacErr::Cycle(by_source.into_keys().min().unwrap())),
+            _ => return Err(AlmanacErr::BrokenChain(starts)),
+        };
+
+        let mut maps = Vec::new();
+        while let Some(map) = by_source.remove(&category) {
+            category = map.destination.clone();
+            maps.push(map);
+        }
+
+        // The chain must not lead back into itself
+        if maps.iter().any(|m| m.source == category) {
+            return Err(AlmanacErr::Cycle(category));
+        }
+
+        // Any map left over was only reachable through a cycle
+        if let Some(source) = by_source.into_keys().min() {
+            return Err(AlmanacErr::Cycle(source));
+        }
+
+        Ok(Almanac { maps })
+    }
+
+    /// Returns the maps that convert `source` values into `destination` values, in order
+    fn path(&self, source: &str, destination: &str) -> Result<&[Map], AlmanacErr> {
+        if source == destination {
+            return Ok(&[]);
+        }
+
+        let not_connected = || AlmanacErr::NotConnected(source.to_owned(), destination.to_owned());
+
+        let start = self
+            .maps
+            .iter()
+            .position(|m| m.source == source)
+            .ok_or_else(not_connected)?;
+        let end = self.maps[start..]
+            .iter()
+            .position(|m| m.destination == destination)
+            .ok_or_else(not_connected)?;
+
+        Ok(&self.maps[start..=start + end])
     }
 }
 
@@ -137,7 +406,149 @@ mod tests {
     // Note, takes about a minute
     #[test]
     fn puzzle_result_test() {
-        let result = run_puzzle_file(&PathBuf::from("tests/input"));
-        assert_eq!(result, 100165128);
+        let result = run_puzzle_file(&PathBuf::from("tests/input"), OverlapPolicy::Error);
+        assert_eq!(result, Ok(100165128));
+    }
+
+    fn convert(
+        almanac: &Almanac,
+        source: &str,
+        destination: &str,
+        value: i64,
+    ) -> Result<i64, AlmanacErr> {
+        let maps = almanac.path(source, destination)?;
+        Ok(maps.iter().fold(value, |v, map| map.map(v)))
+    }
+
+    #[test]
+    fn demo_result() {
+        let result = run_puzzle_file(&PathBuf::from("tests/demo_input"), OverlapPolicy::Error);
+        assert_eq!(result, Ok(46));
+    }
+
+    #[test]
+    fn convert_between_categories() {
+        let input = std::fs::read_to_string("tests/demo_input").unwrap();
+        let almanac = Almanac::from_str(&input).unwrap();
+
+        assert_eq!(convert(&almanac, "seed", "location", 82), Ok(46));
+        assert_eq!(convert(&almanac, "soil", "humidity", 81), Ok(78));
+        assert_eq!(convert(&almanac, "light", "light", 77), Ok(77));
+        assert_eq!(
+            convert(&almanac, "humidity", "soil", 78),
+            Err(AlmanacErr::NotConnected("humidity".into(), "soil".into()))
+        );
+        assert_eq!(
+            convert(&almanac, "seed", "weather", 1),
+            Err(AlmanacErr::NotConnected("seed".into(), "weather".into()))
+        );
+    }
+
+    #[test]
+    fn reject_bad_chains() {
+        let broken = "a-to-b map:\n0 0 1\n\nc-to-d map:\n0 0 1";
+        assert_eq!(
+            Almanac::from_str(broken).err(),
+            Some(AlmanacErr::BrokenChain(vec!["a".into(), "c".into()]))
+        );
+
+        let cyclic = "a-to-b map:\n0 0 1\n\nb-to-c map:\n0 0 1\n\nc-to-b map:\n0 0 1";
+        assert_eq!(
+            Almanac::from_str(cyclic).err(),
+            Some(AlmanacErr::Cycle("b".into()))
+        );
+
+        assert_eq!(Almanac::from_str("").err(), Some(AlmanacErr::Empty));
+        assert_eq!(
+            Almanac::from_str("seeds: 1 2\n\n").err(),
+            Some(AlmanacErr::Empty)
+        );
+        let overflowing = format!("a-to-b map:\n0 0 1\n0 {} 2", i64::MAX - 1);
+        assert_eq!(
+            Almanac::from_str(&overflowing).err(),
+            Some(AlmanacErr::BadEntry(3, format!("0 {} 2", i64::MAX - 1)))
+        );
+        let far = format!("a-to-b map:\n{} 1 1", i64::MIN);
+        assert_eq!(
+            Almanac::from_str(&far).err(),
+            Some(AlmanacErr::BadEntry(2, format!("{} 1 1", i64::MIN)))
+        );
+
+        let repeated = "a-to-b map:\n0 0 1\n\na-to-c map:\n0 0 1";
+        assert_eq!(
+            Almanac::from_str(repeated).err(),
+            Some(AlmanacErr::DuplicateSource("a".into()))
+        );
+        assert_eq!(
+            Almanac::from_str("a-b map:\n0 0 1").err(),
+            Some(AlmanacErr::BadHeader("a-b map:".into()))
+        );
+        assert_eq!(
+            Almanac::from_str("a-to-b map:\n0 0 -1").err(),
+            Some(AlmanacErr::BadEntry(2, "0 0 -1".into()))
+        );
+    }
+
+    #[test]
+    fn validate_map() {
+        let input = "a-to-b map:\n100 0 10\n200 5 10\n300 30 5\n400 40 0";
+        let almanac = Almanac::parse(input, OverlapPolicy::FirstWins).unwrap();
+        assert_eq!(
+            almanac.maps[0].validate(),
+            vec![
+                Issue::ZeroLength { line: 5 },
+                Issue::Overlap {
+                    lines: (2, 3),
+                    range: 5..10
+                },
+                Issue::Gap {
+                    lines: (3, 4),
+                    range: 15..30
+                },
+            ]
+        );
+
+        assert_eq!(convert(&almanac, "a", "b", 7), Ok(107));
+        assert_eq!(convert(&almanac, "a", "b", 12), Ok(207));
+        let almanac = Almanac::parse(input, OverlapPolicy::LastWins).unwrap();
+        assert_eq!(convert(&almanac, "a", "b", 7), Ok(202));
+        assert_eq!(convert(&almanac, "a", "b", 2), Ok(102));
+
+        assert!(matches!(
+            Almanac::from_str(input),
+            Err(AlmanacErr::Overlaps(_, _, found)) if found.len() == 1
+        ));
+    }
+
+    #[test]
+    fn report_issues() {
+        let input = "seeds: 1 2\n\na-to-b map:\n100 0 10\n200 5 10\n\nb-to-c map:\n0 0 5\n9 10 0";
+        let expected = [
+            "a-to-b map:",
+            "  lines 4 and 5 overlap on 5..10",
+            "b-to-c map:",
+            "  line 9 has zero length",
+            "",
+        ];
+        assert_eq!(report(input), Ok(expected.join("\n")));
+
+        let demo = std::fs::read_to_string("tests/demo_input").unwrap();
+        assert_eq!(report(&demo), Ok(String::new()));
+    }
+
+    #[test]
+    fn overlaps_are_an_error() {
+        let input = "seeds: 3 1\n\nseed-to-location map:\n100 0 10\n200 5 10";
+        assert!(matches!(
+            run_puzzle(input, OverlapPolicy::Error),
+            Err(AlmanacErr::Overlaps(..))
+        ));
+        assert_eq!(run_puzzle(input, OverlapPolicy::FirstWins), Ok(103));
+        assert_eq!(
+            run_puzzle(input, OverlapPolicy::Error)
+                .unwrap_err()
+                .to_string(),
+            "seed-to-location map has overlapping entries: lines 4 and 5 overlap on 5..10"
+        );
     }
 }
@@ -1,3 +1,4 @@
+use aoc_common::{checked::CheckedIterator, quadratic::below_zero};
 use clap::Parser;
 use std::path::PathBuf;
 
@@ -14,13 +15,17 @@ fn main() {
     println!("Final: {result}");
 }
 
-fn run_puzzle_file(filename: &PathBuf) -> usize {
+fn run_puzzle_file(filename: &PathBuf) -> u128 {
     run_puzzle(&std::fs::read_to_string(filename).unwrap())
 }
 
-fn run_puzzle(input: &str) -> usize {
+fn run_puzzle(input: &str) -> u128 {
     let races = parse_races(input);
-    races.iter().map(|race| race.get_wins().count()).product()
+    races
+        .iter()
+        .map(|race| race.count_wins())
+        .checked_product()
+        .expect("answer is too big")
 }
 
 fn parse_races(input: &str) -> Vec<Race> {
@@ -42,32 +47,36 @@ fn parse_races(input: &str) -> Vec<Race> {
 
 #[derive(Debug)]
 struct Race {
-    time: u32,
-    distance: u32,
+    time: u128,
+    distance: u128,
 }
 
 impl Race {
-    fn distance(&self, time_held: u32) -> u32 {
-        time_held * (self.time - time_held)
+    /// Distance travelled, `None` if it's too far to fit in a `u128`
+    fn distance(&self, time_held: u128) -> Option<u128> {
+        [time_held, self.time - time_held]
+            .into_iter()
+            .checked_product()
+            .ok()
     }
-    fn is_win(&self, time_held: u32) -> bool {
-        self.distance(time_held) > self.distance
+    fn is_win(&self, time_held: u128) -> bool {
+        // Too far to fit is definitely further than the record
+        self.distance(time_held)
+            .is_none_or(|distance| distance > self.distance)
     }
 
-    fn get_wins(&self) -> std::ops::RangeInclusive<u32> {
-        dbg!(self);
-        let possible_times = 0..=self.time;
-        let min_time = possible_times
-            .clone()
-            .take_while(|time| !self.is_win(*time))
-            .count() as u32;
-        dbg!(min_time);
+    /// Winning hold times are between the roots of `held * (time - held) - distance`
+    fn get_wins(&self) -> Option<std::ops::RangeInclusive<u128>> {
+        let wins = below_zero(self.time, self.distance)?;
 
-        // Winning time is symmetric
-        let max_time = self.time - min_time;
+        // Just outside the range should tie or lose the race
+        debug_assert!(self.is_win(*wins.start()) && !self.is_win(wins.start() - 1));
+        Some(wins)
+    }
 
-        dbg!(max_time);
-        min_time..=max_time
+    fn count_wins(&self) -> u128 {
+        self.get_wins()
+            .map_or(0, |wins| wins.end() - wins.start() + 1)
     }
 }
 
@@ -80,4 +89,26 @@ mod tests {
         let result = run_puzzle_file(&PathBuf::from("tests/input"));
         assert_eq!(result, 1155175);
     }
+
+    #[test]
+    fn tied_record_does_not_win() {
+        let race = Race {
+            time: 30,
+            distance: 200,
+        };
+        assert_eq!(race.get_wins(), Some(11..=19));
+
+        let result = run_puzzle_file(&PathBuf::from("tests/demo_input"));
+        assert_eq!(result, 288);
+    }
+
+    #[test]
+    fn race_too_long_to_square() {
+        // The best distance doesn't fit in a u128, every hold time but the ends wins
+        let race = Race {
+            time: u128::MAX,
+            distance: 1,
+        };
+        assert_eq!(race.count_wins(), u128::MAX - 1);
+    }
 }
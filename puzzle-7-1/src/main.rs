@@ -1,6 +1,10 @@
+use aoc_common::{
+    checked::CheckedIterator,
+    counter::{Alphabet, SmallCounter},
+};
 use clap::Parser;
 use educe::Educe;
-use std::{collections::HashMap, path::PathBuf, str::FromStr};
+use std::{path::PathBuf, str::FromStr};
 
 #[derive(Parser)]
 #[command(author, version, about, long_about = None)]
@@ -24,8 +28,9 @@ fn run_puzzle(input: &str) -> usize {
     deck.sort();
     deck.iter()
         .enumerate()
-        .map(|(idx, hand)| (idx + 1) * hand.bet)
-        .sum()
+        .map(|(idx, hand)| hand.bet.checked_mul(idx + 1).expect("winnings are too big"))
+        .checked_sum()
+        .expect("answer is too big")
 }
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
@@ -65,6 +70,28 @@ impl TryFrom<char> for Card {
     }
 }
 
+impl Alphabet for Card {
+    fn index(&self) -> usize {
+        match self {
+            Card::Value(value) => *value as usize - 2,
+            Card::Jack => 9,
+            Card::Queen => 10,
+            Card::King => 11,
+            Card::Ace => 12,
+        }
+    }
+
+    fn from_index(index: usize) -> Self {
+        match index {
+            0..=8 => Card::Value(index as u8 + 2),
+            9 => Card::Jack,
+            10 => Card::Queen,
+            11 => Card::King,
+            _ => Card::Ace,
+        }
+    }
+}
+
 #[derive(Debug, Eq)]
 struct Hand {
     cards: Vec<Card>,
@@ -73,16 +100,12 @@ struct Hand {
 
 impl Hand {
     fn get_type(&self) -> Type {
-        let mut freq = self
+        let freq = self
             .cards
             .iter()
-            .fold(HashMap::<Card, usize>::new(), |mut freq, card| {
-                *freq.entry(*card).or_default() += 1;
-                freq
-            })
-            .into_iter()
-            .collect::<Vec<(Card, usize)>>();
-        freq.sort_by(|a, b| b.1.cmp(&a.1));
+            .copied()
+            .collect::<SmallCounter<Card, 13>>()
+            .most_common();
 
         match freq[0] {
             (card, 5) => Type::FiveKind(card),
@@ -149,6 +172,8 @@ impl FromStr for Hand {
     }
 }
 
+// Only `Debug` reads the cards each type was made from
+#[allow(dead_code)]
 #[derive(Debug, Copy, Clone, Eq, Educe)]
 #[educe(PartialOrd, Ord)]
 enum Type {
@@ -202,6 +227,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::nonminimal_bool)]
     fn type_order() {
         let king = Card::King;
         let queen = Card::Queen;
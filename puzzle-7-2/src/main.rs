@@ -1,6 +1,10 @@
+use aoc_common::{
+    checked::CheckedIterator,
+    counter::{Alphabet, SmallCounter},
+};
 use clap::Parser;
 use educe::Educe;
-use std::{collections::HashMap, path::PathBuf, str::FromStr};
+use std::{path::PathBuf, str::FromStr};
 
 #[derive(Parser)]
 #[command(author, version, about, long_about = None)]
@@ -24,8 +28,9 @@ fn run_puzzle(input: &str) -> usize {
     deck.sort();
     deck.iter()
         .enumerate()
-        .map(|(idx, hand)| (idx + 1) * hand.bet)
-        .sum()
+        .map(|(idx, hand)| hand.bet.checked_mul(idx + 1).expect("winnings are too big"))
+        .checked_sum()
+        .expect("answer is too big")
 }
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
@@ -65,6 +70,28 @@ impl TryFrom<char> for Card {
     }
 }
 
+impl Alphabet for Card {
+    fn index(&self) -> usize {
+        match self {
+            Card::Joker => 0,
+            Card::Value(value) => *value as usize - 1,
+            Card::Queen => 10,
+            Card::King => 11,
+            Card::Ace => 12,
+        }
+    }
+
+    fn from_index(index: usize) -> Self {
+        match index {
+            0 => Card::Joker,
+            1..=9 => Card::Value(index as u8 + 1),
+            10 => Card::Queen,
+            11 => Card::King,
+            _ => Card::Ace,
+        }
+    }
+}
+
 #[derive(Debug, Eq)]
 struct Hand {
     cards: Vec<Card>,
@@ -73,29 +100,15 @@ struct Hand {
 
 impl Hand {
     fn get_type(&self) -> Type {
-        let mut freq = self
-            .cards
-            .iter()
-            .filter(|card| !matches!(card, Card::Joker))
-            .fold(HashMap::<Card, usize>::new(), |mut freq, card| {
-                *freq.entry(*card).or_default() += 1;
-                freq
-            })
-            .into_iter()
-            .collect::<Vec<(Card, usize)>>();
-        freq.sort_by(|a, b| b.1.cmp(&a.1));
-
-        let num_jokers = self
-            .cards
-            .iter()
-            .filter(|card| matches!(card, Card::Joker))
-            .count();
-
-        if let Some(value) = freq.get_mut(0) {
+        let mut counts: SmallCounter<Card, 13> = self.cards.iter().copied().collect();
+        let num_jokers = counts.remove(Card::Joker);
+        let mut freq = counts.most_common();
+
+        if let Some(value) = freq.first_mut() {
             value.1 += num_jokers
         }
 
-        match freq.get(0) {
+        match freq.first() {
             None => Type::FiveKind(Card::Joker),
             Some((card, 5)) => Type::FiveKind(*card),
             Some((card, 4)) => Type::FourKind(*card),
@@ -161,6 +174,8 @@ impl FromStr for Hand {
     }
 }
 
+// Only `Debug` reads the cards each type was made from
+#[allow(dead_code)]
 #[derive(Debug, Copy, Clone, Eq, Educe)]
 #[educe(PartialOrd, Ord)]
 enum Type {
@@ -214,6 +229,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::nonminimal_bool)]
     fn type_order() {
         let king = Card::King;
         let queen = Card::Queen;